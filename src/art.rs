@@ -1,4 +1,9 @@
+pub mod art_generator;
+pub mod bisection_art;
 pub mod fscs_art;
 pub mod kdfc_art;
 pub mod lhs_art;
+pub mod quasi_random;
+pub mod random_partition_art;
+pub mod rrt_art;
 pub mod rt;
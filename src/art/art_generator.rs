@@ -0,0 +1,306 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{fault::fault_zone::FaultZone, util::point::Point};
+
+use super::{
+    bisection_art::BisectionArt, fscs_art::FscsArt, kdfc_art::KdfcArt, lhs_art::LhsArt,
+    quasi_random::QuasiRandom, random_partition_art::RandomPartitionArt, rrt_art::RrtArt, rt::Rt,
+};
+
+/// Outcome of one run of a generator on a fault zone, see `ArtGenerator::test_measures`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TrialResult {
+    /// Number of test cases generated until the fault zone was found, i.e. the F-measure of the
+    /// run
+    pub f_count: usize,
+    /// Number of failures among the first `n_tests` test cases, i.e. the E-measure of the run
+    pub n_failures: usize,
+    /// Whether the run was cut off before the fault zone was found
+    pub max_tries_reached: bool,
+}
+
+/// Common interface of the test case generation algorithms in `art`.
+///
+/// A generator produces test cases one by one from its input domain and remembers the ones it has
+/// already produced, so that adaptive algorithms can spread the next test case away from them.
+pub trait ArtGenerator {
+    /// Generates the next test case and records it as executed.
+    fn next_test_case(&mut self) -> Point;
+
+    /// Forgets all previously generated test cases, so that the generator can be reused for a new
+    /// run.
+    fn reset(&mut self);
+
+    /// Replaces the random number generator of the generator with one seeded by `seed`, so that
+    /// the following test cases can be reproduced.
+    fn reseed(&mut self, seed: u64);
+
+    /// Returns the number of test cases generated until the fault zone is found.
+    ///
+    /// The generator is reset before the run. The run is cut off after `30 / theta` test cases, in
+    /// which case the cut-off count is returned. A fault zone whose failure rate is not positive
+    /// and finite cannot be found, so its run is cut off right away and 0 is returned.
+    fn test_effectiveness(&mut self, fault_zone: &dyn FaultZone) -> usize {
+        self.test_measures(fault_zone, 0).f_count
+    }
+
+    /// Runs the generator on the fault zone until it is found, like `test_effectiveness`, and for
+    /// at least `n_tests` test cases, counting the failures among the first `n_tests`.
+    fn test_measures(&mut self, fault_zone: &dyn FaultZone, n_tests: usize) -> TrialResult {
+        self.reset();
+
+        let theta = fault_zone.get_theta();
+        let max_tries = if theta > 0.0 && theta.is_finite() {
+            (30.0 / theta) as usize
+        } else {
+            0
+        };
+        let mut f_count = None;
+        let mut n_failures = 0;
+        let mut n_generated = 0;
+
+        while n_generated < n_tests || (f_count.is_none() && n_generated < max_tries) {
+            let test_case = self.next_test_case();
+            n_generated += 1;
+            if fault_zone.find_target(&test_case) {
+                f_count.get_or_insert(n_generated);
+                if n_generated <= n_tests {
+                    n_failures += 1;
+                }
+            }
+        }
+
+        let (f_count, max_tries_reached) = match f_count {
+            Some(n) if n <= max_tries => (n, false),
+            _ => (max_tries, true),
+        };
+
+        TrialResult {
+            f_count,
+            n_failures,
+            max_tries_reached,
+        }
+    }
+
+    /// Generates `n_generated_values` test cases starting from a reset generator.
+    fn test_efficiency(&mut self, n_generated_values: usize) {
+        self.reset();
+
+        for _ in 0..n_generated_values {
+            self.next_test_case();
+        }
+    }
+}
+
+/// Implements `Iterator` for generators as an endless stream of their test cases, so that they
+/// can be used lazily, e.g. `for p in generator.by_ref().take(500) { ... }`.
+macro_rules! test_case_iterator {
+    ($($generator:ident),*) => {
+        $(
+            impl Iterator for $generator<'_> {
+                type Item = Point;
+
+                fn next(&mut self) -> Option<Point> {
+                    Some(self.next_test_case())
+                }
+            }
+        )*
+    };
+}
+
+test_case_iterator!(
+    Rt,
+    FscsArt,
+    LhsArt,
+    KdfcArt,
+    RrtArt,
+    BisectionArt,
+    RandomPartitionArt,
+    QuasiRandom
+);
+
+impl Iterator for dyn ArtGenerator + '_ {
+    type Item = Point;
+
+    fn next(&mut self) -> Option<Point> {
+        Some(self.next_test_case())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::art::{
+        bisection_art::BisectionArt,
+        fscs_art::FscsArt,
+        kdfc_art::{KdfcArt, KdfcVariant},
+        lhs_art::LhsArt,
+        quasi_random::{QuasiRandom, QuasiSequence},
+        random_partition_art::RandomPartitionArt,
+        rrt_art::RrtArt,
+        rt::Rt,
+    };
+
+    #[test]
+    fn test_generators_stay_in_input_domain() {
+        let input_domain = vec![vec![-100, 100], vec![0, 50], vec![-30, 70]];
+
+        let mut generators: Vec<Box<dyn ArtGenerator>> = vec![
+            Box::new(Rt::with_bound(&input_domain)),
+            Box::new(LhsArt::with_bound(&input_domain)),
+            Box::new(FscsArt::with_bound(&input_domain)),
+            Box::new(KdfcArt::with_variant(&input_domain, KdfcVariant::Naive)),
+            Box::new(KdfcArt::with_variant(&input_domain, KdfcVariant::SemiBalanced)),
+            Box::new(KdfcArt::with_variant(&input_domain, KdfcVariant::LimitedBalanced)),
+            Box::new(KdfcArt::with_variant(&input_domain, KdfcVariant::Approximate)),
+            Box::new(RrtArt::with_bound(&input_domain)),
+            Box::new(BisectionArt::with_bound(&input_domain)),
+            Box::new(RandomPartitionArt::with_bound(&input_domain)),
+            Box::new(QuasiRandom::with_sequence(&input_domain, QuasiSequence::Halton)),
+            Box::new(QuasiRandom::with_sequence(&input_domain, QuasiSequence::Sobol)),
+        ];
+
+        for generator in generators.iter_mut() {
+            for _ in 0..2 {
+                generator.reset();
+                for _ in 0..50 {
+                    let p = generator.next_test_case();
+                    assert_eq!(p.n, input_domain.len());
+                    (0..p.n).for_each(|d| {
+                        assert!(p.coordinates[d] >= input_domain[d][0] as f32);
+                        assert!(p.coordinates[d] <= input_domain[d][1] as f32);
+                    });
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_generators_as_iterators() {
+        let input_domain = vec![vec![-100, 100], vec![0, 50]];
+
+        let mut kdfc = KdfcArt::with_variant(&input_domain, KdfcVariant::LimitedBalanced);
+        assert_eq!(kdfc.by_ref().take(20).count(), 20);
+        assert_eq!(kdfc.size, 20);
+
+        let lhs = LhsArt::with_bound(&input_domain);
+        assert_eq!(lhs.take(25).count(), 25);
+
+        let mut fscs: Box<dyn ArtGenerator> = Box::new(FscsArt::with_bound(&input_domain));
+        assert_eq!(fscs.by_ref().take(10).count(), 10);
+    }
+
+    #[test]
+    fn test_reseeded_generators_are_reproducible() {
+        let input_domain = vec![vec![-100, 100], vec![0, 50], vec![-30, 70]];
+
+        let mut generators: Vec<Box<dyn ArtGenerator>> = vec![
+            Box::new(Rt::with_bound(&input_domain)),
+            Box::new(LhsArt::with_bound(&input_domain)),
+            Box::new(FscsArt::with_bound(&input_domain)),
+            Box::new(KdfcArt::with_variant(&input_domain, KdfcVariant::LimitedBalanced)),
+            Box::new(RrtArt::with_bound(&input_domain)),
+            Box::new(BisectionArt::with_bound(&input_domain)),
+            Box::new(RandomPartitionArt::with_bound(&input_domain)),
+            Box::new(QuasiRandom::with_sequence(&input_domain, QuasiSequence::Halton)),
+            Box::new(QuasiRandom::with_sequence(&input_domain, QuasiSequence::Sobol)),
+        ];
+
+        for generator in generators.iter_mut() {
+            generator.reseed(42);
+            generator.reset();
+            let first = generator.by_ref().take(30).collect::<Vec<_>>();
+
+            generator.reseed(42);
+            generator.reset();
+            let second = generator.by_ref().take(30).collect::<Vec<_>>();
+
+            assert!(first
+                .iter()
+                .zip(second.iter())
+                .all(|(p1, p2)| p1.coordinates == p2.coordinates));
+        }
+    }
+
+    #[test]
+    fn test_effectiveness_with_custom_fault_zone() {
+        // Failure region of all inputs whose first coordinate is above 0
+        struct HalfDomain;
+
+        impl FaultZone for HalfDomain {
+            fn find_target(&self, p: &Point) -> bool {
+                p.coordinates[0] > 0.0
+            }
+
+            fn get_theta(&self) -> f64 {
+                0.5
+            }
+        }
+
+        let input_domain = vec![vec![-100, 100], vec![0, 50]];
+        let mut fscs = FscsArt::with_bound(&input_domain);
+        fscs.reseed(1);
+        let n_tests = fscs.test_effectiveness(&HalfDomain);
+        assert!((1..=60).contains(&n_tests));
+    }
+
+    #[test]
+    fn test_fault_found_on_last_try() {
+        // Failure region hit by the `hit_at`th test case only
+        struct NthTestCase {
+            hit_at: usize,
+            n_calls: std::cell::Cell<usize>,
+        }
+
+        impl FaultZone for NthTestCase {
+            fn find_target(&self, _p: &Point) -> bool {
+                self.n_calls.set(self.n_calls.get() + 1);
+                self.n_calls.get() == self.hit_at
+            }
+
+            fn get_theta(&self) -> f64 {
+                0.3
+            }
+        }
+
+        let input_domain = vec![vec![-100, 100], vec![0, 50]];
+        let mut rt = Rt::with_bound(&input_domain);
+        // 30 / 0.3 = 100 test cases are allowed
+        for (hit_at, expected) in [(100, (100, false)), (101, (100, true))] {
+            let fault_zone = NthTestCase {
+                hit_at,
+                n_calls: std::cell::Cell::new(0),
+            };
+            let result = rt.test_measures(&fault_zone, 0);
+            assert_eq!((result.f_count, result.max_tries_reached), expected);
+        }
+    }
+
+    #[test]
+    fn test_fault_zone_without_failure_rate() {
+        struct Empty(f64);
+
+        impl FaultZone for Empty {
+            fn find_target(&self, _p: &Point) -> bool {
+                false
+            }
+
+            fn get_theta(&self) -> f64 {
+                self.0
+            }
+        }
+
+        let input_domain = vec![vec![-100, 100], vec![0, 50]];
+        let mut rt = Rt::with_bound(&input_domain);
+        for theta in [0.0, -0.5, f64::NAN, f64::INFINITY] {
+            assert_eq!(
+                rt.test_measures(&Empty(theta), 10),
+                TrialResult {
+                    f_count: 0,
+                    n_failures: 0,
+                    max_tries_reached: true,
+                }
+            );
+        }
+    }
+}
@@ -0,0 +1,162 @@
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::util::point::Point;
+
+use super::art_generator::ArtGenerator;
+
+/// ART by bisection (B-ART)
+///
+/// The input domain is divided into a grid of equally sized cells. Each test case is generated
+/// inside a random untested cell, i.e. a cell without any executed test case. When all cells are
+/// tested, every cell is bisected along the next dimension in turn and the testing continues with
+/// the untested halves.
+#[derive(Debug)]
+pub struct BisectionArt<'this> {
+    pub input_domain: &'this [Vec<i32>],
+    pub rng: StdRng,
+    tcp: Vec<Point>,
+    /// Number of cells per dimension
+    n_parts: Vec<usize>,
+    /// Dimension to bisect next
+    next_split: usize,
+    untested_cells: Vec<usize>,
+}
+
+impl Default for BisectionArt<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'this> BisectionArt<'this> {
+    pub fn new() -> BisectionArt<'this> {
+        BisectionArt {
+            input_domain: &[],
+            rng: StdRng::from_os_rng(),
+            tcp: vec![],
+            n_parts: vec![],
+            next_split: 0,
+            untested_cells: vec![],
+        }
+    }
+
+    pub fn with_bound(bound: &'this [Vec<i32>]) -> Self {
+        let mut bart = Self {
+            input_domain: bound,
+            ..Self::new()
+        };
+        bart.reset();
+        bart
+    }
+
+    /// Returns the flat index of the cell containing `p`.
+    fn get_cell_index(&self, p: &Point) -> usize {
+        let mut index = 0;
+        for d in (0..self.input_domain.len()).rev() {
+            let lower = self.input_domain[d][0] as f64;
+            let width = (self.input_domain[d][1] - self.input_domain[d][0]) as f64;
+            let cell =
+                ((p.coordinates[d] as f64 - lower) / width * self.n_parts[d] as f64) as usize;
+            index = index * self.n_parts[d] + cell.min(self.n_parts[d] - 1);
+        }
+        index
+    }
+
+    /// Returns the `[lower, upper]` bounds of each dimension of the cell with flat index `index`.
+    fn get_cell_boundary(&self, mut index: usize) -> Vec<Vec<f64>> {
+        (0..self.input_domain.len())
+            .map(|d| {
+                let cell = index % self.n_parts[d];
+                index /= self.n_parts[d];
+                let lower = self.input_domain[d][0] as f64;
+                let step = (self.input_domain[d][1] - self.input_domain[d][0]) as f64
+                    / self.n_parts[d] as f64;
+                vec![lower + cell as f64 * step, lower + (cell + 1) as f64 * step]
+            })
+            .collect()
+    }
+
+    /// Bisects all cells along the next dimension and collects the halves without test cases.
+    fn bisect(&mut self) {
+        self.n_parts[self.next_split] *= 2;
+        self.next_split = (self.next_split + 1) % self.input_domain.len();
+
+        let n_cells = self.n_parts.iter().product::<usize>();
+        let mut tested = vec![false; n_cells];
+        self.tcp
+            .iter()
+            .for_each(|p| tested[self.get_cell_index(p)] = true);
+        self.untested_cells = (0..n_cells).filter(|&i| !tested[i]).collect();
+    }
+}
+
+impl ArtGenerator for BisectionArt<'_> {
+    fn next_test_case(&mut self) -> Point {
+        if self.input_domain.is_empty() {
+            return Point::new(0);
+        }
+        // The grid is set up on the first test case if the generator was created by `new`.
+        if self.n_parts.len() != self.input_domain.len() {
+            self.reset();
+        }
+        while self.untested_cells.is_empty() {
+            self.bisect();
+        }
+
+        let pick_index = self.rng.random_range(0..self.untested_cells.len());
+        let cell = self.untested_cells.swap_remove(pick_index);
+        let test_case = Point::generate_rand_p_in(&self.get_cell_boundary(cell), &mut self.rng);
+
+        self.tcp.push(test_case.clone());
+        test_case
+    }
+
+    fn reset(&mut self) {
+        self.tcp.clear();
+        self.n_parts = vec![1; self.input_domain.len()];
+        self.next_split = 0;
+        self.untested_cells = vec![0];
+    }
+
+    fn reseed(&mut self, seed: u64) {
+        self.rng = StdRng::seed_from_u64(seed);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_one_test_case_per_cell() {
+        let input_domain = vec![vec![-5000, 5000], vec![0, 100], vec![-10, 30]];
+        let mut bart = BisectionArt::with_bound(&input_domain);
+        bart.reseed(3);
+
+        // 2^6 cells are tested by the first 64 test cases
+        for _ in 0..64 {
+            bart.next_test_case();
+        }
+        assert_eq!(bart.n_parts, vec![4, 4, 4]);
+        let mut cells = bart
+            .tcp
+            .iter()
+            .map(|p| bart.get_cell_index(p))
+            .collect::<Vec<_>>();
+        cells.sort();
+        cells.dedup();
+        assert_eq!(cells.len(), 64);
+    }
+
+    #[test]
+    fn test_new_without_reset() {
+        let input_domain = vec![vec![0, 10], vec![0, 10]];
+
+        let mut bart = BisectionArt::new();
+        assert_eq!(bart.next_test_case().n, 0);
+        bart.input_domain = &input_domain;
+        assert_eq!(bart.by_ref().take(16).count(), 16);
+        assert_eq!(bart.n_parts, vec![4, 4]);
+    }
+}
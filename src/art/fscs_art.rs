@@ -1,9 +1,16 @@
-use crate::{fault::fault_zone::FaultZone, util::point::Point};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use crate::util::point::Point;
+
+use super::art_generator::ArtGenerator;
 
 #[derive(Debug)]
 pub struct FscsArt<'this> {
     pub cand_num: i32,
     pub input_domain: &'this [Vec<i32>],
+    pub rng: StdRng,
+    tcp: Vec<Point>,
 }
 
 impl Default for FscsArt<'_> {
@@ -16,14 +23,23 @@ impl<'this> FscsArt<'this> {
     pub fn new() -> FscsArt<'this> {
         FscsArt {
             cand_num: 10,
-            input_domain: &[]
+            input_domain: &[],
+            rng: StdRng::from_os_rng(),
+            tcp: vec![],
         }
     }
 
     pub fn with_n(n: i32) -> Self {
         Self {
             cand_num: n,
-            input_domain: &[],
+            ..Self::new()
+        }
+    }
+
+    pub fn with_bound(bound: &'this [Vec<i32>]) -> Self {
+        Self {
+            input_domain: bound,
+            ..Self::new()
         }
     }
 
@@ -50,61 +66,31 @@ impl<'this> FscsArt<'this> {
         });
         furthest_index
     }
+}
 
-    pub fn test_fscs_art_effectiveness(&'this mut self, bound: &'this [Vec<i32>], fzb: &FaultZone) -> i32 {
-        self.input_domain = bound; // Set input_domain
-        let mut generated_num = 0;
-        let max_try = (30.0 / fzb.get_theta()) as i32;
-        // println!("max_try: {max_try}");
-        let mut tcp = Vec::with_capacity((max_try + 2) as usize);
-        let mut cand_p = Vec::with_capacity(self.cand_num as usize);
-
-        tcp.push(Point::generate_rand_p(bound));
-        // println!("tcp[0]: {:?}", tcp[0]);
-        generated_num += 1;
-
-        loop {
-            cand_p.clear();
-            for _ in 0..self.cand_num {
-                cand_p.push(Point::generate_rand_p(bound));
-            }
-
-            let selected = self.find_furthest_candidate(&tcp, generated_num as usize, &cand_p);
-            tcp.push(cand_p[selected].clone());
-            generated_num += 1;
-
-
-            // if (generated_num % 1000) == 0 {
-            //     println!("generated_num: {generated_num}");
-            // }
-
-            if fzb.find_target(&tcp[generated_num as usize - 1]) {
-                break;
-            }
-
-            if generated_num >= max_try {
-                break;
-            }
+impl ArtGenerator for FscsArt<'_> {
+    fn next_test_case(&mut self) -> Point {
+        if self.tcp.is_empty() {
+            let test_case = Point::generate_rand_p(self.input_domain, &mut self.rng);
+            self.tcp.push(test_case.clone());
+            return test_case;
         }
-        // println!("generated_num: {generated_num}");
-        generated_num
-    }
 
-    pub fn test_fscs_art_efficiency(&'this mut self, num: i32, bound: &'this [Vec<i32>]) {
-        self.input_domain = bound; // Set input_domain
+        let mut cand_p = (0..self.cand_num)
+            .map(|_| Point::generate_rand_p(self.input_domain, &mut self.rng))
+            .collect::<Vec<_>>();
 
-        let mut tcp = Vec::with_capacity(num as usize);
-        let mut cand_p = Vec::with_capacity(self.cand_num as usize);
+        let selected = self.find_furthest_candidate(&self.tcp, self.tcp.len(), &cand_p);
+        let test_case = cand_p.swap_remove(selected);
+        self.tcp.push(test_case.clone());
+        test_case
+    }
 
-        tcp.push(Point::generate_rand_p(bound));
+    fn reset(&mut self) {
+        self.tcp.clear();
+    }
 
-        for j in 1..num {
-            cand_p.clear();
-            for _ in 0..self.cand_num {
-                cand_p.push(Point::generate_rand_p(bound));
-            }
-            let selected = self.find_furthest_candidate(&tcp, j as usize, &cand_p);
-            tcp.push(cand_p[selected].clone());
-        }
+    fn reseed(&mut self, seed: u64) {
+        self.rng = StdRng::seed_from_u64(seed);
     }
 }
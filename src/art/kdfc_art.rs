@@ -1,14 +1,79 @@
-use crate::{
-    fault::fault_zone::FaultZone,
-    util::{node::Node, point::Point},
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use serde::{Deserialize, Serialize};
+
+use crate::util::{
+    kd_tree::{self, KdTree, NodeId},
+    point::Point,
 };
 
-#[derive(Debug)]
+use super::art_generator::ArtGenerator;
+
+/// The tree insertion and nearest neighbour search strategy of `KdfcArt`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KdfcVariant {
+    /// Splits dimensions in turn and searches the nearest neighbour exhaustively.
+    Naive,
+    /// Splits dimensions by `split_select` and searches the nearest neighbour exhaustively.
+    SemiBalanced,
+    /// Splits dimensions by `split_select` and limits the nearest neighbour search by backtracking.
+    LimitedBalanced,
+    /// Splits dimensions by `split_select` and searches an approximate nearest neighbour, at most
+    /// `1 + epsilon` times as far as the nearest one.
+    Approximate,
+}
+
+/// The number of nodes to visit in the limited backtracking nearest neighbour search of
+/// `KdfcVariant::LimitedBalanced`, depending on the number of points in the tree.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "schedule", rename_all = "kebab-case")]
+pub enum BackNumSchedule {
+    /// `ceil(coefficient * log2(size))`, where the coefficient defaults to `(d + 1 / d)^2 / 2` for
+    /// `d` dimensions
+    Logarithmic { coefficient: Option<f64> },
+    /// The same number of nodes for every tree size
+    Constant { back_num: i32 },
+}
+
+impl Default for BackNumSchedule {
+    fn default() -> Self {
+        BackNumSchedule::Logarithmic { coefficient: None }
+    }
+}
+
+impl BackNumSchedule {
+    /// Returns the number of nodes to visit when the tree holds `size` points in a `n_dims`
+    /// dimensional input domain.
+    pub fn back_num(&self, n_dims: usize, size: usize) -> i32 {
+        match *self {
+            BackNumSchedule::Logarithmic { coefficient } => {
+                if size < 2 {
+                    return 1;
+                }
+                let d = n_dims as f64;
+                let coefficient = coefficient.unwrap_or(1.0 / 2.0 * (d + 1.0 / d).powi(2));
+                (coefficient * ((size as f64).ln() / 2.0f64.ln())).ceil() as i32
+            }
+            BackNumSchedule::Constant { back_num } => back_num,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
 pub struct KdfcArt<'this> {
-    pub root: Node,
+    pub tree: KdTree,
     pub size: usize,
     pub candidate_num: i32,
     pub input_domain: &'this [Vec<i32>],
+    pub variant: KdfcVariant,
+    pub back_num_schedule: BackNumSchedule,
+    /// Relative error bound of the nearest neighbour distance of `KdfcVariant::Approximate`
+    pub epsilon: f64,
+    /// Test cases executed before the generator was created, kept in the tree across resets
+    pub executed: Vec<Vec<f64>>,
+    /// Rebalances the tree whenever it holds a multiple of this many points, if given
+    pub rebalance_every: Option<usize>,
+    pub rng: StdRng,
 }
 
 impl Default for KdfcArt<'_> {
@@ -20,477 +85,362 @@ impl Default for KdfcArt<'_> {
 impl<'this> KdfcArt<'this> {
     pub fn new() -> Self {
         Self {
-            root: Node::new(),
+            tree: KdTree::default(),
             size: 0,
             candidate_num: 10,
             input_domain: &[],
+            variant: KdfcVariant::SemiBalanced,
+            back_num_schedule: BackNumSchedule::default(),
+            epsilon: 0.0,
+            executed: Vec::new(),
+            rebalance_every: None,
+            rng: StdRng::from_os_rng(),
         }
     }
 
     pub fn with_bound(bound: &'this [Vec<i32>]) -> Self {
-        let mut kda = Self::new();
-        kda.input_domain = bound;
-        kda.root.boundary = Some(vec![vec![0.0; 2]; bound.len()]);
-
-        (0..bound.len()).for_each(|i| {
-            kda.root.boundary.as_mut().unwrap()[i][0] = bound[i][0] as f64;
-            kda.root.boundary.as_mut().unwrap()[i][1] = bound[i][1] as f64;
-        });
+        Self {
+            tree: KdTree::new(bound),
+            input_domain: bound,
+            ..Self::new()
+        }
+    }
 
+    pub fn with_variant(bound: &'this [Vec<i32>], variant: KdfcVariant) -> Self {
+        let mut kda = Self::with_bound(bound);
+        kda.variant = variant;
         kda
     }
 
-    pub fn get_tree_path(&self, point: &Point) -> Vec<&Node> {
-        let mut path = Vec::new();
-        let mut path_node = &self.root;
-
-        loop {
-            path.push(path_node);
-            if let Some(path_node_point) = path_node.point.as_ref() {
-                let split = path_node.split;
-                if path_node_point.coordinates[split] > point.coordinates[split] {
-                    if path_node.left.is_none() {
-                        break;
-                    }
-                    path_node = path_node.left.as_ref().unwrap();
-                } else {
-                    if path_node.right.is_none() {
-                        break;
-                    }
-                    path_node = path_node.right.as_ref().unwrap();
-                }
-            } else {
-                break;
-            }
-        }
-        path
+    /// Creates a generator that continues from an already executed test suite. The suite is
+    /// bulk-loaded into a balanced tree instead of being inserted one test case at a time.
+    pub fn with_executed(
+        bound: &'this [Vec<i32>],
+        variant: KdfcVariant,
+        executed: &[Point],
+    ) -> Self {
+        let mut kda = Self::with_variant(bound, variant);
+        kda.executed = executed
+            .iter()
+            .map(|p| p.coordinates.iter().map(|&c| c as f64).collect())
+            .collect();
+        kda.reset();
+        kda
     }
 
-    pub fn judge_direction(&self, p: &Point, node: &Node) -> i32 {
-        if p.coordinates[node.split] < node.point.as_ref().unwrap().coordinates[node.split] {
-            0
-        } else {
-            1
-        }
+    /// Rebuilds the tree as a balanced tree of the same points.
+    pub fn rebalance(&mut self) {
+        self.tree.rebalance();
     }
 
-    pub fn get_min_dis_by_all(&self, p: &Point) -> f64 {
-        let path = self.get_tree_path(p);
-        let mut distance = f64::MAX;
-
-        for path_node in path.iter().rev() {
-            if self.is_cross_split_line(p, distance, path_node) {
-                let d = Point::get_distance(p, path_node.point.as_ref().unwrap());
-                if distance > d as f64 {
-                    distance = d as f64;
-                }
-
-                let direction = self.judge_direction(p, path_node);
-
-                let temp_node = if direction == 0 {
-                    path_node.right.as_ref()
-                } else {
-                    path_node.left.as_ref()
-                };
-
-                if let Some(temp_node) = temp_node {
-                    let mut queue = Vec::new();
-                    queue.push(temp_node);
-
-                    while let Some(temp_node) = queue.pop() {
-                        let direction = self.judge_direction(p, temp_node);
-                        if self.is_cross_split_line(p, distance, temp_node) {
-                            let d = Point::get_distance(p, temp_node.point.as_ref().unwrap());
-                            if distance > d as f64 {
-                                distance = d as f64;
-                            }
-
-                            if direction == 1 {
-                                if let Some(left) = &temp_node.left {
-                                    queue.push(left);
-                                }
-                            } else if let Some(right) = &temp_node.right {
-                                queue.push(right);
-                            }
-                        }
+    /// Returns the distance from `p` to its nearest neighbour in the tree, searching the whole
+    /// tree.
+    pub fn get_min_dis_by_all(&self, p: &[f64]) -> f64 {
+        self.tree
+            .nearest(p)
+            .map_or(f64::MAX, |nearest| nearest.distance)
+    }
 
-                        if direction == 0 {
-                            if let Some(left) = &temp_node.left {
-                                queue.push(left);
-                            }
-                        } else if let Some(right) = &temp_node.right {
-                            queue.push(right);
-                        }
-                    }
-                }
-            }
-        }
-        distance
+    /// Returns a distance from `p` to a neighbour in the tree that is at most `1 + epsilon` times
+    /// the distance to the nearest neighbour.
+    pub fn get_min_dis_by_approximation(&self, p: &[f64], epsilon: f64) -> f64 {
+        self.tree
+            .approx_nearest(p, epsilon)
+            .map_or(f64::MAX, |nearest| nearest.distance)
     }
 
-    pub fn get_min_dis_by_backtracking(&self, p: &Point, back: i32) -> f64 {
+    /// Returns the distance from `p` to the nearest neighbour found in the first `back` nodes
+    /// visited while backtracking from the leaf of `p`.
+    ///
+    /// The search climbs from the leaf of `p` to the root and searches the other side of every
+    /// node whose split line is closer to `p` than the nearest neighbour so far.
+    pub fn get_min_dis_by_backtracking(&self, p: &[f64], back: i32) -> f64 {
         let mut num = 0;
-        let path = self.get_tree_path(p);
+        let mut limit_reached = || {
+            num += 1;
+            num == back
+        };
         let mut distance = f64::MAX;
 
-        for path_node in path.iter().rev() {
-            if self.is_cross_split_line(p, distance, path_node) {
-                let d = Point::get_distance(p, path_node.point.as_ref().unwrap());
-                if distance > d as f64 {
-                    distance = d as f64;
-                }
-                num += 1;
-                if num == back {
+        let mut path_node = self.tree.leaf_for(p);
+        while let Some(id) = path_node {
+            if self.is_cross_split_line(p, distance, id) {
+                distance = distance.min(kd_tree::distance(p, self.tree.point(id)));
+                if limit_reached() {
                     return distance;
                 }
 
-                let direction = self.judge_direction(p, path_node);
-                let temp_node = if direction == 0 {
-                    path_node.right.as_ref()
-                } else {
-                    path_node.left.as_ref()
-                };
-
-                if let Some(temp_node) = temp_node {
-                    let mut queue = Vec::new();
-                    queue.push(temp_node);
-
-                    while let Some(temp_node) = queue.pop() {
-                        let direction = self.judge_direction(p, temp_node);
-                        if self.is_cross_split_line(p, distance, temp_node) {
-                            let d = Point::get_distance(p, temp_node.point.as_ref().unwrap());
-                            if distance > d as f64 {
-                                distance = d as f64;
-                            }
-                            num += 1;
-                            if num == back {
-                                return distance;
-                            }
-
-                            if direction == 1 {
-                                if let Some(left) = &temp_node.left {
-                                    queue.push(left);
-                                }
-                            } else if let Some(right) = &temp_node.right {
-                                queue.push(right);
-                            }
-                        }
-
-                        if direction == 0 {
-                            if let Some(left) = &temp_node.left {
-                                queue.push(left);
-                            }
-                        } else if let Some(right) = &temp_node.right {
-                            queue.push(right);
+                let mut stack = Vec::from_iter(self.tree.child_away(id, p));
+                while let Some(temp_node) = stack.pop() {
+                    if self.is_cross_split_line(p, distance, temp_node) {
+                        distance = distance.min(kd_tree::distance(p, self.tree.point(temp_node)));
+                        if limit_reached() {
+                            return distance;
                         }
+                        stack.extend(self.tree.child_away(temp_node, p));
                     }
+                    stack.extend(self.tree.child_towards(temp_node, p));
                 }
-            } else {
-                num += 1;
-                if num == back {
-                    return distance;
-                }
+            } else if limit_reached() {
+                return distance;
             }
+            path_node = self.tree.node(id).parent;
         }
         distance
     }
 
-    pub fn is_cross_split_line(&self, p: &Point, distance: f64, node: &Node) -> bool {
-        let node_p = node.point.as_ref().unwrap();
-        if (node_p.coordinates[node.split] - p.coordinates[node.split]).abs() as f64 >= distance {
-            return false;
-        }
-        true
+    pub fn is_cross_split_line(&self, p: &[f64], distance: f64, node: NodeId) -> bool {
+        let split = self.tree.node(node).split;
+        (self.tree.split_value(node) - p[split]).abs() < distance
     }
 
-    pub fn insert_point_by_strategy(&mut self, p: &Point) {
-        if self.root.point.is_none() {
-            self.root.deep = 1;
-            self.root.point = Some(p.clone());
-            self.root.boundary = Some(vec![vec![0.0; 2]; p.n]);
-            for i in 0..p.n {
-                self.root.boundary.as_mut().unwrap()[i][0] = self.input_domain[i][0] as f64;
-                self.root.boundary.as_mut().unwrap()[i][1] = self.input_domain[i][1] as f64;
-            }
-            self.root.split = Self::split_select(self.root.boundary.as_ref().unwrap(), p);
-        } else {
-            let mut ntemp = &mut self.root;
-            let mut n = &mut Node::new();
-
-            while let Some(ntemp_p) = ntemp.point.as_ref() {
-                if ntemp_p.coordinates[ntemp.split] > p.coordinates[ntemp.split] {
-                    if ntemp.left.is_none() {
-                        ntemp.left = Some(Box::new(Node::new()));
-                        n = ntemp.left.as_mut().unwrap();
-                        break;
-                    }
-                    ntemp = ntemp.left.as_mut().unwrap();
-                } else {
-                    if ntemp.right.is_none() {
-                        ntemp.right = Some(Box::new(Node::new()));
-                        n = ntemp.right.as_mut().unwrap();
-                        break;
-                    }
-                    ntemp = ntemp.right.as_mut().unwrap();
-                }
-            }
-
-            n.point = Some(p.clone());
-            n.boundary = Some(vec![vec![0.0; 2]; p.n]);
-            n.deep = ntemp.deep + 1;
-            for i in 0..p.n {
-                n.boundary.as_mut().unwrap()[i][0] = ntemp.boundary.as_ref().unwrap()[i][0];
-                n.boundary.as_mut().unwrap()[i][1] = ntemp.boundary.as_ref().unwrap()[i][1];
-            }
-            if n.point.as_ref().unwrap().coordinates[ntemp.split]
-                < ntemp.point.as_ref().unwrap().coordinates[ntemp.split]
-            {
-                n.boundary.as_mut().unwrap()[ntemp.split][1] =
-                    ntemp.point.as_ref().unwrap().coordinates[ntemp.split] as f64;
-            } else {
-                n.boundary.as_mut().unwrap()[ntemp.split][0] =
-                    ntemp.point.as_ref().unwrap().coordinates[ntemp.split] as f64;
-            }
-            let boundary = n.boundary.as_ref().unwrap();
-            n.split = Self::split_select(boundary, p);
-        }
+    pub fn insert_point_by_strategy(&mut self, p: &[f64]) {
+        self.tree
+            .insert(p, |lower, upper, _| Self::split_by_spread(lower, upper, p));
         self.size += 1;
     }
 
-    pub fn insert_point_by_turn(&mut self, p: &Point) {
-        if self.root.point.is_none() {
-            self.root.point = Some(p.clone());
-            self.root.split = 0;
-            self.root.deep = 1;
-        } else {
-            let mut ntemp = &mut self.root;
-            let n = loop {
-                let ntemp_p = ntemp.point.as_ref().unwrap();
-
-                if ntemp_p.coordinates[ntemp.split] > p.coordinates[ntemp.split] {
-                    if ntemp.left.is_none() {
-                        ntemp.left = Some(Box::new(Node::new()));
-                        break ntemp.left.as_mut().unwrap();
-                    }
-                    ntemp = ntemp.left.as_mut().unwrap();
-                } else {
-                    if ntemp.right.is_none() {
-                        ntemp.right = Some(Box::new(Node::new()));
-                        break ntemp.right.as_mut().unwrap();
-                    }
-                    ntemp = ntemp.right.as_mut().unwrap();
-                }
-            };
-
-            n.point = Some(p.clone());
-            n.deep = ntemp.deep + 1;
-            if ntemp.split == (p.coordinates.len() - 1) {
-                n.split = 0;
-            } else {
-                n.split = ntemp.split + 1;
-            }
-        }
+    pub fn insert_point_by_turn(&mut self, p: &[f64]) {
+        let n_dims = p.len();
+        self.tree.insert(p, |_, _, parent_split| {
+            parent_split.map_or(0, |split| (split + 1) % n_dims)
+        });
         self.size += 1;
     }
 
-    pub fn test_naive_kdfc_effectiveness(&mut self, fault_zone: &FaultZone) {
-        let p = Point::generate_rand_p(self.input_domain);
-        self.insert_point_by_turn(&p);
-        if fault_zone.find_target(&p) {
-            return;
-        }
-
-        loop {
-            let mut can_d = Vec::new();
-            for _ in 0..self.candidate_num {
-                can_d.push(Point::generate_rand_p(self.input_domain));
-            }
+    pub fn split_select(boundary: &[Vec<f64>], p: &Point) -> usize {
+        let lower = boundary.iter().map(|b| b[0]).collect::<Vec<_>>();
+        let upper = boundary.iter().map(|b| b[1]).collect::<Vec<_>>();
+        let p = p.coordinates.iter().map(|&c| c as f64).collect::<Vec<_>>();
+        Self::split_by_spread(&lower, &upper, &p)
+    }
 
-            let mut final_case = &can_d[0];
-            let mut distance = self.get_min_dis_by_all(final_case);
+    /// Returns the dimension in which splitting the region at `p` leaves the most even parts.
+    fn split_by_spread(lower: &[f64], upper: &[f64], p: &[f64]) -> usize {
+        let mut rate = 0.0;
+        let mut split = 0;
 
-            (1..can_d.len()).for_each(|c| {
-                let d = self.get_min_dis_by_all(&can_d[c]);
-                if distance < d {
-                    distance = d;
-                    final_case = &can_d[c];
-                }
-            });
-
-            self.insert_point_by_turn(final_case);
-            // if (self.size % 1000) == 0 {
-            //     println!("final_case: {:?}", final_case);
-            //     println!("size: {}", self.size);
-            // }
-            if fault_zone.find_target(final_case) {
-                break;
+        (0..p.len()).for_each(|i| {
+            let length = upper[i] - lower[i];
+            let lx1 = upper[i] - p[i];
+            let lx2 = p[i] - lower[i];
+            let spread =
+                length * (1.0 - (lx1 / length) * (lx1 / length) - (lx2 / length) * (lx2 / length));
+            if rate < spread {
+                rate = spread;
+                split = i;
             }
-        }
+        });
+        split
     }
 
-    // pub fn test_semi_bal_kdfc_effectiveness(&mut self, fzb: &dyn FaultZone) {
-    pub fn test_semi_bal_kdfc_effectiveness(&mut self, fzb: &FaultZone) {
-        let p = Point::generate_rand_p(self.input_domain);
-        self.insert_point_by_strategy(&p);
-        if fzb.find_target(&p) {
-            return;
+    fn insert(&mut self, p: &[f64]) {
+        match self.variant {
+            KdfcVariant::Naive => self.insert_point_by_turn(p),
+            _ => self.insert_point_by_strategy(p),
         }
-
-        loop {
-            let mut can_d = Vec::new();
-            for _ in 0..self.candidate_num {
-                can_d.push(Point::generate_rand_p(self.input_domain));
-            }
-
-            let mut final_case = &can_d[0];
-            let mut distance = self.get_min_dis_by_all(final_case);
-
-            (1..can_d.len()).for_each(|c| {
-                let d = self.get_min_dis_by_all(&can_d[c]);
-                if distance < d {
-                    distance = d;
-                    final_case = &can_d[c];
-                }
-            });
-
-            self.insert_point_by_strategy(final_case);
-            // if (self.size % 1000) == 0 {
-            //     println!("final_case: {:?}", final_case);
-            //     println!("size: {}", self.size);
-            // }
-            if fzb.find_target(final_case) {
-                break;
-            }
+        if self
+            .rebalance_every
+            .is_some_and(|n| self.size.is_multiple_of(n))
+        {
+            self.rebalance();
         }
     }
+}
 
-    pub fn test_lim_bal_kdfc_effectiveness(&mut self, fzb: &FaultZone, back_num: &[i32]) {
-        let p = Point::generate_rand_p(self.input_domain);
-        self.insert_point_by_strategy(&p);
-        if fzb.find_target(&p) {
-            return;
-        }
-
-        loop {
-            let mut can_d = Vec::new();
-            for _ in 0..self.candidate_num {
-                can_d.push(Point::generate_rand_p(self.input_domain));
-            }
+impl ArtGenerator for KdfcArt<'_> {
+    fn next_test_case(&mut self) -> Point {
+        let to_f64 = |p: &Point| p.coordinates.iter().map(|&c| c as f64).collect::<Vec<_>>();
 
-            let mut final_case = &can_d[0];
-            let back = back_num[self.size];
-            let mut distance = self.get_min_dis_by_backtracking(final_case, back);
+        // The tree is set up on the first test case if `input_domain` was set after `new`.
+        if self.tree.n_dims() != self.input_domain.len() {
+            self.reset();
+        }
 
-            (1..can_d.len()).for_each(|c| {
-                let d = self.get_min_dis_by_backtracking(&can_d[c], back);
-                if distance < d {
-                    distance = d;
-                    final_case = &can_d[c];
-                }
-            });
-
-            self.insert_point_by_strategy(final_case);
-            // if (self.size % 1000) == 0 {
-            //     println!("final_case: {:?}", final_case);
-            //     println!("size: {}", self.size);
-            // }
-            if fzb.find_target(final_case) {
-                break;
-            }
+        if self.size == 0 {
+            let p = Point::generate_rand_p(self.input_domain, &mut self.rng);
+            self.insert(&to_f64(&p));
+            return p;
         }
-    }
 
-    pub fn test_naive_kdfc_efficiency(&mut self, point_num: i32) {
-        let p = Point::generate_rand_p(self.input_domain);
-        self.insert_point_by_turn(&p);
+        let mut can_d = Vec::new();
+        for _ in 0..self.candidate_num {
+            can_d.push(Point::generate_rand_p(self.input_domain, &mut self.rng));
+        }
 
-        for _ in 1..point_num {
-            let mut can_d = Vec::new();
-            for _ in 0..self.candidate_num {
-                can_d.push(Point::generate_rand_p(self.input_domain));
+        let back = self
+            .back_num_schedule
+            .back_num(self.input_domain.len(), self.size);
+        let get_min_dis = |p: &[f64]| match self.variant {
+            KdfcVariant::LimitedBalanced => self.get_min_dis_by_backtracking(p, back),
+            KdfcVariant::Approximate => self.get_min_dis_by_approximation(p, self.epsilon),
+            _ => self.get_min_dis_by_all(p),
+        };
+
+        let can_d_f64 = can_d.iter().map(to_f64).collect::<Vec<_>>();
+        let mut final_case_i = 0;
+        let mut distance = get_min_dis(&can_d_f64[0]);
+
+        (1..can_d.len()).for_each(|c| {
+            let d = get_min_dis(&can_d_f64[c]);
+            if distance < d {
+                distance = d;
+                final_case_i = c;
             }
+        });
 
-            let mut final_case = can_d[0].clone();
-            let mut distance = self.get_min_dis_by_all(&final_case);
-
-            (1..can_d.len()).for_each(|c| {
-                let d = self.get_min_dis_by_all(&can_d[c]);
-                if distance < d {
-                    distance = d;
-                    final_case = can_d[c].clone();
-                }
-            });
+        self.insert(&can_d_f64[final_case_i]);
+        can_d.swap_remove(final_case_i)
+    }
 
-            self.insert_point_by_turn(&final_case);
-        }
+    fn reset(&mut self) {
+        self.tree.reset(self.input_domain);
+        self.tree.build(&self.executed);
+        self.size = self.executed.len();
     }
 
-    pub fn test_semi_bal_kdfc_efficiency(&mut self, point_num: i32) {
-        let p = Point::generate_rand_p(self.input_domain);
-        self.insert_point_by_strategy(&p);
+    fn reseed(&mut self, seed: u64) {
+        self.rng = StdRng::seed_from_u64(seed);
+    }
+}
 
-        for _ in 1..point_num {
-            let mut can_d = Vec::new();
-            for _ in 0..self.candidate_num {
-                can_d.push(Point::generate_rand_p(self.input_domain));
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::art::fscs_art::FscsArt;
+
+    #[test]
+    fn test_nearest_distance_matches_brute_force() {
+        let input_domain = vec![vec![-100, 100], vec![0, 50], vec![-30, 70]];
+        let mut rng = StdRng::seed_from_u64(37);
+
+        for variant in [KdfcVariant::Naive, KdfcVariant::SemiBalanced] {
+            let mut kdfc = KdfcArt::with_variant(&input_domain, variant);
+            kdfc.reseed(5);
+            let points = kdfc
+                .by_ref()
+                .take(300)
+                .map(|p| p.coordinates.iter().map(|&c| c as f64).collect::<Vec<_>>())
+                .collect::<Vec<_>>();
+
+            for _ in 0..100 {
+                let q = Point::generate_rand_p(&input_domain, &mut rng)
+                    .coordinates
+                    .iter()
+                    .map(|&c| c as f64)
+                    .collect::<Vec<_>>();
+                let brute_force = points
+                    .iter()
+                    .map(|p| kd_tree::distance(&q, p))
+                    .fold(f64::MAX, f64::min);
+                assert_eq!(kdfc.get_min_dis_by_all(&q), brute_force);
+                assert!(kdfc.get_min_dis_by_backtracking(&q, 3) >= brute_force);
             }
-
-            let mut final_case = can_d[0].clone();
-            let mut distance = self.get_min_dis_by_all(&final_case);
-
-            (1..can_d.len()).for_each(|c| {
-                let d = self.get_min_dis_by_all(&can_d[c]);
-                if distance < d {
-                    distance = d;
-                    final_case = can_d[c].clone();
-                }
-            });
-
-            self.insert_point_by_strategy(&final_case);
         }
     }
 
-    pub fn test_lim_bal_kdfc_efficiency(&mut self, point_num: i32, back_num: &[i32]) {
-        let p = Point::generate_rand_p(self.input_domain);
-        self.insert_point_by_strategy(&p);
-
-        for _ in 1..point_num {
-            let mut can_d = Vec::new();
-            for _ in 0..self.candidate_num {
-                can_d.push(Point::generate_rand_p(self.input_domain));
-            }
+    #[test]
+    fn test_continue_from_executed_suite() {
+        let input_domain = vec![vec![0, 1000], vec![0, 1000]];
+        let executed = (0..1000)
+            .map(|i| Point {
+                coordinates: vec![i as f32, (i * 7 % 1000) as f32],
+                n: 2,
+            })
+            .collect::<Vec<_>>();
+
+        let mut kdfc = KdfcArt::with_executed(&input_domain, KdfcVariant::SemiBalanced, &executed);
+        kdfc.rebalance_every = Some(1024);
+        kdfc.reseed(11);
+        let max_depth = |kdfc: &KdfcArt| {
+            (0..kdfc.tree.len())
+                .map(|id| kdfc.tree.node(id).depth)
+                .max()
+        };
+        assert_eq!(kdfc.size, 1000);
+        assert_eq!(max_depth(&kdfc), Some(10));
+
+        let next = kdfc
+            .by_ref()
+            .take(24)
+            .map(|p| p.coordinates)
+            .collect::<Vec<_>>();
+        assert_eq!(kdfc.size, 1024);
+        assert_eq!(max_depth(&kdfc), Some(11));
+        for p in &next {
+            assert!(executed.iter().all(|e| &e.coordinates != p));
+        }
 
-            let mut final_case = can_d[0].clone();
-            let back = back_num[self.size];
-            let mut distance = self.get_min_dis_by_backtracking(&final_case, back);
+        kdfc.reset();
+        kdfc.reseed(11);
+        assert_eq!(kdfc.size, 1000);
+        let again = kdfc.by_ref().take(24).map(|p| p.coordinates);
+        assert_eq!(again.collect::<Vec<_>>(), next);
+    }
 
-            (1..can_d.len()).for_each(|c| {
-                let d = self.get_min_dis_by_backtracking(&can_d[c], back);
-                if distance < d {
-                    distance = d;
-                    final_case = can_d[c].clone();
+    #[test]
+    fn test_branch_and_bound_matches_brute_force() {
+        let mut rng = StdRng::seed_from_u64(47);
+
+        for n_dims in [2, 3, 5, 8, 12, 16, 20] {
+            let input_domain = vec![vec![-5000, 5000]; n_dims];
+            for variant in [KdfcVariant::Naive, KdfcVariant::SemiBalanced] {
+                let mut kdfc = KdfcArt::with_variant(&input_domain, variant);
+                let tcp = (0..1000)
+                    .map(|_| Point::generate_rand_p(&input_domain, &mut rng))
+                    .collect::<Vec<_>>();
+                for p in &tcp {
+                    kdfc.insert(&p.coordinates.iter().map(|&c| c as f64).collect::<Vec<_>>());
                 }
-            });
-
-            self.insert_point_by_strategy(&final_case);
+                let fscs = FscsArt::with_bound(&input_domain);
+
+                for _ in 0..50 {
+                    let cand_p = (0..10)
+                        .map(|_| Point::generate_rand_p(&input_domain, &mut rng))
+                        .collect::<Vec<_>>();
+                    let min_dis = cand_p
+                        .iter()
+                        .map(|c| {
+                            let c = c.coordinates.iter().map(|&x| x as f64).collect::<Vec<_>>();
+                            let brute_force = tcp
+                                .iter()
+                                .map(|p| {
+                                    let p = p.coordinates.iter().map(|&x| x as f64);
+                                    kd_tree::distance(&c, &p.collect::<Vec<_>>())
+                                })
+                                .fold(f64::MAX, f64::min);
+                            let min_dis = kdfc.get_min_dis_by_all(&c);
+                            assert_eq!(min_dis, brute_force, "{n_dims} dimensions");
+                            min_dis
+                        })
+                        .collect::<Vec<_>>();
+
+                    let furthest = (0..cand_p.len())
+                        .max_by(|&a, &b| min_dis[a].total_cmp(&min_dis[b]))
+                        .unwrap();
+                    assert_eq!(
+                        furthest,
+                        fscs.find_furthest_candidate(&tcp, tcp.len(), &cand_p),
+                        "{n_dims} dimensions"
+                    );
+                }
+            }
         }
     }
 
-    pub fn split_select(boundary: &[Vec<f64>], p: &Point) -> usize {
-        let mut rate = 0.0;
-        let mut split = 0;
-
-        (0..p.n).for_each(|i| {
-            let length = boundary[i][1] - boundary[i][0];
-            let lx1 = boundary[i][1] - p.coordinates[i] as f64;
-            let lx2 = p.coordinates[i] as f64 - boundary[i][0];
-            let spread =
-                length * (1.0 - (lx1 / length) * (lx1 / length) - (lx2 / length) * (lx2 / length));
-            if rate < spread {
-                rate = spread;
-                split = i;
-            }
-        });
-        split
+    #[test]
+    fn test_new_with_input_domain_set_later() {
+        KdfcArt::default().rebalance();
+
+        let input_domain = vec![vec![0, 100], vec![0, 100]];
+        let mut kdfc = KdfcArt::new();
+        kdfc.input_domain = &input_domain;
+        assert_eq!(kdfc.by_ref().take(50).count(), 50);
+        assert_eq!(
+            (kdfc.size, kdfc.tree.len(), kdfc.tree.n_dims()),
+            (50, 50, 2)
+        );
+        kdfc.rebalance();
+        assert_eq!(kdfc.tree.len(), 50);
     }
 }
@@ -1,5 +1,9 @@
-use crate::{fault::fault_zone::FaultZone, util::point::Point};
+use crate::util::point::Point;
+use rand::rngs::StdRng;
 use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+
+use super::art_generator::ArtGenerator;
 
 #[derive(Debug)]
 pub struct LhsArt<'this> {
@@ -9,7 +13,10 @@ pub struct LhsArt<'this> {
     pub n_partitions: usize,
     pub input_domain: &'this [Vec<i32>],
     pub exhaustive: bool,
+    pub rng: StdRng,
     randomised_point_indices: Vec<u32>,
+    test_case_suite: Vec<Point>,
+    next_test_case_i: usize,
 }
 
 impl Default for LhsArt<'_> {
@@ -24,16 +31,24 @@ impl<'this> LhsArt<'this> {
             n_partitions: 10,
             input_domain: &[],
             exhaustive: false,
+            rng: StdRng::from_os_rng(),
             randomised_point_indices: vec![],
+            test_case_suite: vec![],
+            next_test_case_i: 0,
         }
     }
 
     pub fn with_partition_count(n_partitions: usize) -> Self {
         Self {
             n_partitions,
-            input_domain: &[],
-            exhaustive: false,
-            randomised_point_indices: vec![],
+            ..Self::new()
+        }
+    }
+
+    pub fn with_bound(bound: &'this [Vec<i32>]) -> Self {
+        Self {
+            input_domain: bound,
+            ..Self::new()
         }
     }
 
@@ -47,7 +62,7 @@ impl<'this> LhsArt<'this> {
     /// Two-dimensional Latin hypercube sampling is not much more complicated and is usually performed with software. Assuming your two variables, x1 and x2 are independent, you follow the one-dimensional method to come up with one dimensional samples for x1 and x2 separately. Once you have two lists of samples, you combine them, randomly, into two-dimensional pairs.
     ///
     /// For n-dimensional Latin hypercube sampling the same method is used.
-    fn populate_test_cases_random(&self, existing_test_cases: &mut Vec<Point>) {
+    fn populate_test_cases_random(&mut self, existing_test_cases: &mut Vec<Point>) {
         let n = self.input_domain.len();
         let mut points = Vec::with_capacity(self.n_partitions);
 
@@ -71,12 +86,12 @@ impl<'this> LhsArt<'this> {
             let point = Point {
                 coordinates: (0..n)
                     .map(|d| {
-                        let pick_index = rand::random_range(0..lower_bounds[d].len());
+                        let pick_index = self.rng.random_range(0..lower_bounds[d].len());
                         let lower_bound = lower_bounds[d].remove(pick_index);
 
                         let upper_bound =
                             f32::min(lower_bound as f32 + steps[d] as f32, ranges[d].end as f32);
-                        rand::random_range(lower_bound as f32..upper_bound)
+                        self.rng.random_range(lower_bound as f32..upper_bound)
                     })
                     .collect(),
                 n,
@@ -128,7 +143,7 @@ impl<'this> LhsArt<'this> {
             self.n_partitions
         );
         self.randomised_point_indices = (0..n_points).map(|x| x as u32).collect::<Vec<u32>>();
-        self.randomised_point_indices.shuffle(&mut rand::rng());
+        self.randomised_point_indices.shuffle(&mut self.rng);
     }
 
     /// Populates a test case suite with random points using latin hypercube sampling
@@ -162,7 +177,7 @@ impl<'this> LhsArt<'this> {
                             lower_bounds[d] as f32 + steps[d] as f32,
                             self.input_domain[d][1] as f32,
                         );
-                        rand::random_range(lower_bounds[d] as f32..upper_bound)
+                        self.rng.random_range(lower_bounds[d] as f32..upper_bound)
                     })
                     .collect(),
                 n,
@@ -179,54 +194,30 @@ impl<'this> LhsArt<'this> {
             false => self.populate_test_cases_random(existing_test_cases),
         }
     }
+}
 
-    pub fn test_lhs_art_effectiveness(&'this mut self, fzb: &FaultZone) -> usize {
-        let max_tries = (30.0 / fzb.get_theta()) as usize;
-        let mut test_case_suite = Vec::with_capacity(self.n_partitions);
-
-        self.populate_test_cases(&mut test_case_suite);
-
-        let mut test_case_i = 0;
-
-        while test_case_i < max_tries {
-            // println!("lhs_art test_case_i: {}", test_case_i + 1);
-            // check if the fault zone is found with current test case
-            let test_case = &test_case_suite[test_case_i];
-            if fzb.find_target(test_case) {
-                break;
-            }
-
-            test_case_i += 1;
-
-            // if all existing test cases are checked, generate new ones
-            if test_case_i == test_case_suite.len() {
-                self.populate_test_cases(&mut test_case_suite);
-            }
-        }
-
-        if test_case_i == max_tries {
-            println!("lhs art max tries ({max_tries}) reached")
+impl ArtGenerator for LhsArt<'_> {
+    fn next_test_case(&mut self) -> Point {
+        // if all existing test cases are used, generate new ones
+        if self.next_test_case_i == self.test_case_suite.len() {
+            let mut test_case_suite = std::mem::take(&mut self.test_case_suite);
+            self.populate_test_cases(&mut test_case_suite);
+            self.test_case_suite = test_case_suite;
         }
 
-        // println!("done");
-        test_case_i + 1
+        let test_case = self.test_case_suite[self.next_test_case_i].clone();
+        self.next_test_case_i += 1;
+        test_case
     }
 
-    pub fn test_lhs_art_efficiency(
-        &'this mut self,
-        n_generated_values: usize,
-        bound: &'this [Vec<i32>],
-    ) {
-        self.input_domain = bound; // Set input_domain
-        let mut test_case_suite = Vec::with_capacity(n_generated_values);
+    fn reset(&mut self) {
+        self.randomised_point_indices.clear();
+        self.test_case_suite.clear();
+        self.next_test_case_i = 0;
+    }
 
-        if n_generated_values <= self.n_partitions {
-            self.populate_test_cases(&mut test_case_suite);
-        } else {
-            while test_case_suite.len() < n_generated_values {
-                self.populate_test_cases(&mut test_case_suite);
-            }
-        }
+    fn reseed(&mut self, seed: u64) {
+        self.rng = StdRng::seed_from_u64(seed);
     }
 }
 
@@ -0,0 +1,351 @@
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+use crate::util::{point::Point, seed::derive_seed};
+
+use super::art_generator::ArtGenerator;
+
+/// Primitive polynomials and initial direction numbers of the Sobol sequence for dimensions 2 to
+/// 21 (Joe and Kuo), as `(degree, coefficients, initial direction numbers)`. The first dimension
+/// is the van der Corput sequence in base 2.
+const SOBOL_DIRECTIONS: [(u32, u32, &[u32]); 20] = [
+    (1, 0, &[1]),
+    (2, 1, &[1, 3]),
+    (3, 1, &[1, 3, 1]),
+    (3, 2, &[1, 1, 1]),
+    (4, 1, &[1, 1, 3, 3]),
+    (4, 4, &[1, 3, 5, 13]),
+    (5, 2, &[1, 1, 5, 5, 17]),
+    (5, 4, &[1, 1, 5, 5, 5]),
+    (5, 7, &[1, 1, 7, 11, 19]),
+    (5, 11, &[1, 1, 5, 1, 1]),
+    (5, 13, &[1, 1, 1, 3, 11]),
+    (5, 14, &[1, 3, 5, 5, 31]),
+    (6, 1, &[1, 3, 3, 9, 7, 49]),
+    (6, 13, &[1, 1, 1, 15, 21, 21]),
+    (6, 16, &[1, 3, 1, 13, 27, 49]),
+    (6, 19, &[1, 1, 1, 15, 7, 5]),
+    (6, 22, &[1, 3, 1, 15, 13, 25]),
+    (6, 25, &[1, 1, 5, 5, 19, 61]),
+    (7, 1, &[1, 3, 7, 11, 23, 15, 103]),
+    (7, 4, &[1, 3, 7, 13, 13, 15, 69]),
+];
+
+/// Maximum number of input domain dimensions of `QuasiSequence::Sobol`
+pub const SOBOL_MAX_DIMS: usize = SOBOL_DIRECTIONS.len() + 1;
+
+/// Number of bits of the Sobol sequence, i.e. the sequence repeats after `2^SOBOL_BITS` points
+const SOBOL_BITS: usize = 32;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum QuasiSequence {
+    /// Radical inverses in the first `d` prime bases
+    Halton,
+    /// Base 2 digital sequence with Joe-Kuo direction numbers, up to `SOBOL_MAX_DIMS` dimensions
+    Sobol,
+}
+
+/// Randomisation of a quasi-random sequence. Without scrambling every run generates the same test
+/// cases.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Scrambling {
+    None,
+    /// Adds a random shift modulo 1 to each coordinate (Cranley-Patterson rotation)
+    RandomShift,
+    /// Permutes each digit randomly depending on the preceding digits (Owen's nested uniform
+    /// scrambling), which keeps the stratification of the sequence
+    #[default]
+    Owen,
+}
+
+/// Quasi-random (low-discrepancy) sequence mapped onto the input domain
+///
+/// The `i`th test case is the `i`th point of the sequence in the unit hypercube, optionally
+/// scrambled, and scaled to the input domain. The first point of the sequence, the origin, is
+/// skipped.
+#[derive(Debug)]
+pub struct QuasiRandom<'this> {
+    pub sequence: QuasiSequence,
+    pub scrambling: Scrambling,
+    pub input_domain: &'this [Vec<i32>],
+    pub rng: StdRng,
+    /// Index of the next point of the sequence, 0 before the first test case of a run
+    index: u64,
+    /// Halton bases or the flattened `SOBOL_BITS` Sobol direction numbers of each dimension
+    generators: Vec<u32>,
+    shifts: Vec<f64>,
+    owen_seeds: Vec<u64>,
+}
+
+impl Default for QuasiRandom<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'this> QuasiRandom<'this> {
+    pub fn new() -> QuasiRandom<'this> {
+        QuasiRandom {
+            sequence: QuasiSequence::Sobol,
+            scrambling: Scrambling::default(),
+            input_domain: &[],
+            rng: StdRng::from_os_rng(),
+            index: 0,
+            generators: vec![],
+            shifts: vec![],
+            owen_seeds: vec![],
+        }
+    }
+
+    pub fn with_bound(bound: &'this [Vec<i32>]) -> Self {
+        Self {
+            input_domain: bound,
+            ..Self::new()
+        }
+    }
+
+    pub fn with_sequence(bound: &'this [Vec<i32>], sequence: QuasiSequence) -> Self {
+        Self {
+            sequence,
+            ..Self::with_bound(bound)
+        }
+    }
+
+    /// Prepares the sequence and draws the scrambling of a new run.
+    fn start(&mut self) {
+        let n_dims = self.input_domain.len();
+        self.generators = match self.sequence {
+            QuasiSequence::Halton => first_primes(n_dims),
+            QuasiSequence::Sobol => sobol_direction_numbers(n_dims),
+        };
+        self.shifts = match self.scrambling {
+            Scrambling::RandomShift => (0..n_dims).map(|_| self.rng.random()).collect(),
+            _ => vec![0.0; n_dims],
+        };
+        self.owen_seeds = match self.scrambling {
+            Scrambling::Owen => (0..n_dims).map(|_| self.rng.random()).collect(),
+            _ => vec![],
+        };
+        self.index = 1;
+    }
+
+    /// Returns coordinate `dim` of point `index` of the Halton sequence in the unit interval.
+    fn halton(&self, dim: usize, mut index: u64) -> f64 {
+        let base = self.generators[dim];
+        let inv_base = 1.0 / base as f64;
+        let mut scale = inv_base;
+        let mut u = 0.0;
+
+        match self.owen_seeds.get(dim) {
+            Some(&seed) => {
+                // Scramble enough digits for single precision coordinates
+                let n_digits = (32.0 / (base as f64).log2()).ceil() as usize;
+                let mut prefix = seed;
+                for _ in 0..n_digits.max(n_digits_in_base(index, base)) {
+                    let digit = (index % base as u64) as u32;
+                    index /= base as u64;
+                    u += permute_digit(digit, base, prefix) as f64 * scale;
+                    prefix = derive_seed(prefix, digit as u64);
+                    scale *= inv_base;
+                }
+            }
+            None => {
+                while index > 0 {
+                    u += (index % base as u64) as f64 * scale;
+                    index /= base as u64;
+                    scale *= inv_base;
+                }
+            }
+        }
+        u
+    }
+
+    /// Returns coordinate `dim` of point `index` of the Sobol sequence in the unit interval.
+    fn sobol(&self, dim: usize, index: u64) -> f64 {
+        let directions = &self.generators[dim * SOBOL_BITS..(dim + 1) * SOBOL_BITS];
+        let gray_code = index ^ (index >> 1);
+        let mut x = (0..SOBOL_BITS)
+            .filter(|&bit| (gray_code >> bit) & 1 == 1)
+            .fold(0u32, |x, bit| x ^ directions[bit]);
+
+        if let Some(&seed) = self.owen_seeds.get(dim) {
+            let mut prefix = seed;
+            let mut scrambled = 0u32;
+            for bit in (0..SOBOL_BITS).rev() {
+                let digit = (x >> bit) & 1;
+                scrambled |= permute_digit(digit, 2, prefix) << bit;
+                prefix = derive_seed(prefix, digit as u64);
+            }
+            x = scrambled;
+        }
+        x as f64 / (1u64 << SOBOL_BITS) as f64
+    }
+}
+
+impl ArtGenerator for QuasiRandom<'_> {
+    fn next_test_case(&mut self) -> Point {
+        if self.index == 0 {
+            self.start();
+        }
+
+        let mut p = Point::new(self.input_domain.len());
+        (0..p.n).for_each(|i| {
+            let u = match self.sequence {
+                QuasiSequence::Halton => self.halton(i, self.index),
+                QuasiSequence::Sobol => self.sobol(i, self.index),
+            };
+            let u = (u + self.shifts[i]).fract();
+            let (lower, upper) = (self.input_domain[i][0], self.input_domain[i][1]);
+            p.coordinates[i] = (lower as f64 + (upper - lower) as f64 * u) as f32;
+        });
+        self.index += 1;
+        p
+    }
+
+    fn reset(&mut self) {
+        self.index = 0;
+    }
+
+    fn reseed(&mut self, seed: u64) {
+        self.rng = StdRng::seed_from_u64(seed);
+    }
+}
+
+fn first_primes(n: usize) -> Vec<u32> {
+    let mut primes: Vec<u32> = Vec::with_capacity(n);
+    let mut candidate = 2;
+    while primes.len() < n {
+        if primes
+            .iter()
+            .take_while(|&&p| p * p <= candidate)
+            .all(|&p| candidate % p != 0)
+        {
+            primes.push(candidate);
+        }
+        candidate += 1;
+    }
+    primes
+}
+
+fn n_digits_in_base(mut index: u64, base: u32) -> usize {
+    let mut n_digits = 0;
+    while index > 0 {
+        index /= base as u64;
+        n_digits += 1;
+    }
+    n_digits
+}
+
+/// Returns the direction numbers of the first `n_dims` dimensions of the Sobol sequence, as
+/// `SOBOL_BITS` numbers per dimension.
+fn sobol_direction_numbers(n_dims: usize) -> Vec<u32> {
+    assert!(
+        n_dims <= SOBOL_MAX_DIMS,
+        "the Sobol sequence supports at most {SOBOL_MAX_DIMS} dimensions, got {n_dims}"
+    );
+
+    let mut directions = Vec::with_capacity(n_dims * SOBOL_BITS);
+    directions.extend((0..SOBOL_BITS).map(|bit| 1u32 << (SOBOL_BITS - 1 - bit)));
+
+    for &(degree, coefficients, initial) in SOBOL_DIRECTIONS.iter().take(n_dims.saturating_sub(1)) {
+        let s = degree as usize;
+        let mut v = vec![0u32; SOBOL_BITS];
+        for k in 0..SOBOL_BITS {
+            v[k] = if k < s {
+                initial[k] << (SOBOL_BITS - 1 - k)
+            } else {
+                let mut vk = v[k - s] ^ (v[k - s] >> s);
+                for l in 1..s {
+                    if (coefficients >> (s - 1 - l)) & 1 == 1 {
+                        vk ^= v[k - l];
+                    }
+                }
+                vk
+            };
+        }
+        directions.extend(v);
+    }
+    directions
+}
+
+/// Applies the random permutation of the digits of `base` selected by the hash of the preceding
+/// digits, `prefix`, to `digit`.
+fn permute_digit(digit: u32, base: u32, prefix: u64) -> u32 {
+    // Fisher-Yates shuffle driven by the prefix hash
+    let mut permutation = (0..base).collect::<Vec<_>>();
+    for i in (1..base as usize).rev() {
+        let j = (derive_seed(prefix, i as u64) % (i as u64 + 1)) as usize;
+        permutation.swap(i, j);
+    }
+    permutation[digit as usize]
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_unscrambled_sequences() {
+        let input_domain = vec![vec![0, 1], vec![0, 1]];
+
+        let mut sobol = QuasiRandom::with_sequence(&input_domain, QuasiSequence::Sobol);
+        sobol.scrambling = Scrambling::None;
+        let points = sobol
+            .by_ref()
+            .take(3)
+            .map(|p| p.coordinates)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            points,
+            vec![vec![0.5, 0.5], vec![0.75, 0.25], vec![0.25, 0.75]]
+        );
+
+        let mut halton = QuasiRandom::with_sequence(&input_domain, QuasiSequence::Halton);
+        halton.scrambling = Scrambling::None;
+        let points = halton
+            .by_ref()
+            .take(3)
+            .map(|p| p.coordinates)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            points,
+            vec![
+                vec![0.5, 1.0 / 3.0],
+                vec![0.25, 2.0 / 3.0],
+                vec![0.75, 1.0 / 9.0]
+            ]
+        );
+    }
+
+    #[test]
+    fn test_owen_scrambling_keeps_stratification() {
+        let input_domain = vec![vec![0, 1000], vec![-500, 500], vec![0, 10]];
+
+        for (sequence, bases) in [
+            (QuasiSequence::Sobol, [2, 2, 2]),
+            (QuasiSequence::Halton, [2, 3, 5]),
+        ] {
+            let mut generator = QuasiRandom::with_sequence(&input_domain, sequence);
+            generator.reseed(7);
+            generator.start();
+
+            // The first base^2 points fall into distinct intervals of length 1 / base^2
+            for (d, &base) in bases.iter().enumerate() {
+                let n_intervals = base * base;
+                let mut intervals = (0..n_intervals as u64)
+                    .map(|i| match sequence {
+                        QuasiSequence::Halton => generator.halton(d, i),
+                        QuasiSequence::Sobol => generator.sobol(d, i),
+                    })
+                    .map(|u| (u * n_intervals as f64) as usize)
+                    .collect::<Vec<_>>();
+                intervals.sort();
+                intervals.dedup();
+                assert_eq!(intervals.len(), n_intervals);
+            }
+        }
+    }
+}
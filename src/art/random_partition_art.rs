@@ -0,0 +1,152 @@
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use crate::util::point::Point;
+
+use super::{art_generator::ArtGenerator, kdfc_art::KdfcArt};
+
+/// A sub-region of the input domain, ordered by its volume
+#[derive(Debug)]
+struct Region {
+    volume: f64,
+    boundary: Vec<Vec<f64>>,
+}
+
+impl Region {
+    fn new(boundary: Vec<Vec<f64>>) -> Self {
+        let volume = boundary.iter().map(|b| b[1] - b[0]).product();
+        Region { volume, boundary }
+    }
+}
+
+impl PartialEq for Region {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Region {}
+
+impl PartialOrd for Region {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Region {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.volume.total_cmp(&other.volume)
+    }
+}
+
+/// ART by random partitioning (RP-ART)
+///
+/// Each test case is generated randomly inside the largest region of the input domain. The region
+/// is then split in two at the test case, along the dimension chosen by `KdfcArt::split_select`.
+#[derive(Debug)]
+pub struct RandomPartitionArt<'this> {
+    pub input_domain: &'this [Vec<i32>],
+    pub rng: StdRng,
+    regions: BinaryHeap<Region>,
+}
+
+impl Default for RandomPartitionArt<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'this> RandomPartitionArt<'this> {
+    pub fn new() -> RandomPartitionArt<'this> {
+        RandomPartitionArt {
+            input_domain: &[],
+            rng: StdRng::from_os_rng(),
+            regions: BinaryHeap::new(),
+        }
+    }
+
+    pub fn with_bound(bound: &'this [Vec<i32>]) -> Self {
+        let mut rpart = Self {
+            input_domain: bound,
+            ..Self::new()
+        };
+        rpart.reset();
+        rpart
+    }
+}
+
+impl ArtGenerator for RandomPartitionArt<'_> {
+    fn next_test_case(&mut self) -> Point {
+        if self.input_domain.is_empty() {
+            return Point::new(0);
+        }
+        // The input domain is the only region on the first test case if the generator was
+        // created by `new`.
+        if self.regions.is_empty() {
+            self.reset();
+        }
+        let region = self.regions.pop().unwrap();
+        let test_case = Point::generate_rand_p_in(&region.boundary, &mut self.rng);
+
+        let split = KdfcArt::split_select(&region.boundary, &test_case);
+        let mut lower = region.boundary.clone();
+        let mut upper = region.boundary;
+        lower[split][1] = test_case.coordinates[split] as f64;
+        upper[split][0] = test_case.coordinates[split] as f64;
+        self.regions.push(Region::new(lower));
+        self.regions.push(Region::new(upper));
+
+        test_case
+    }
+
+    fn reset(&mut self) {
+        self.regions.clear();
+        self.regions.push(Region::new(
+            self.input_domain
+                .iter()
+                .map(|b| vec![b[0] as f64, b[1] as f64])
+                .collect(),
+        ));
+    }
+
+    fn reseed(&mut self, seed: u64) {
+        self.rng = StdRng::seed_from_u64(seed);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_regions_partition_input_domain() {
+        let input_domain = vec![vec![-5000, 5000], vec![0, 100], vec![-10, 30]];
+        let mut rpart = RandomPartitionArt::with_bound(&input_domain);
+        rpart.reseed(9);
+
+        let mut largest = f64::INFINITY;
+        for _ in 0..500 {
+            let volume = rpart.regions.peek().unwrap().volume;
+            assert!(volume <= largest);
+            largest = volume;
+            rpart.next_test_case();
+        }
+
+        let total = rpart.regions.iter().map(|r| r.volume).sum::<f64>();
+        assert!((total - 10000.0 * 100.0 * 40.0).abs() / total < 1e-6);
+    }
+
+    #[test]
+    fn test_new_without_reset() {
+        let input_domain = vec![vec![0, 10], vec![0, 10]];
+
+        let mut rpart = RandomPartitionArt::new();
+        assert_eq!(rpart.next_test_case().n, 0);
+        rpart.input_domain = &input_domain;
+        assert_eq!(rpart.by_ref().take(16).count(), 16);
+        assert_eq!(rpart.regions.len(), 17);
+    }
+}
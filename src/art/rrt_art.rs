@@ -0,0 +1,109 @@
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use crate::util::{
+    geometry::{domain_volume, unit_ball_volume},
+    point::Point,
+};
+
+use super::art_generator::ArtGenerator;
+
+/// Restricted random testing (Chan et al.)
+///
+/// Every executed test case is surrounded by an exclusion zone, a ball whose radius is chosen so
+/// that the zones together cover `exclusion_ratio` times the volume of the input domain. Random
+/// candidates are generated until one falls outside all exclusion zones.
+#[derive(Debug)]
+pub struct RrtArt<'this> {
+    /// Total volume of the exclusion zones relative to the volume of the input domain
+    pub exclusion_ratio: f64,
+    /// Number of rejected candidates after which the next candidate is accepted regardless of the
+    /// exclusion zones, in case the zones cover the whole input domain
+    pub max_rejections: usize,
+    pub input_domain: &'this [Vec<i32>],
+    pub rng: StdRng,
+    tcp: Vec<Point>,
+}
+
+impl Default for RrtArt<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'this> RrtArt<'this> {
+    pub fn new() -> RrtArt<'this> {
+        RrtArt {
+            exclusion_ratio: 1.5,
+            max_rejections: 10_000,
+            input_domain: &[],
+            rng: StdRng::from_os_rng(),
+            tcp: vec![],
+        }
+    }
+
+    pub fn with_bound(bound: &'this [Vec<i32>]) -> Self {
+        Self {
+            input_domain: bound,
+            ..Self::new()
+        }
+    }
+
+    /// Returns the radius of each exclusion zone when `n_executed` test cases have been executed.
+    pub fn exclusion_radius(&self, n_executed: usize) -> f64 {
+        let n_dims = self.input_domain.len();
+        let zone_volume =
+            self.exclusion_ratio * domain_volume(self.input_domain) / n_executed as f64;
+        (zone_volume / unit_ball_volume(n_dims)).powf(1.0 / n_dims as f64)
+    }
+
+    fn is_excluded(&self, candidate: &Point, radius: f32) -> bool {
+        self.tcp
+            .iter()
+            .any(|p| Point::get_distance(candidate, p) < radius)
+    }
+}
+
+impl ArtGenerator for RrtArt<'_> {
+    fn next_test_case(&mut self) -> Point {
+        let radius = self.exclusion_radius(self.tcp.len()) as f32;
+
+        let mut candidate = Point::generate_rand_p(self.input_domain, &mut self.rng);
+        let mut n_rejections = 0;
+        while n_rejections < self.max_rejections && self.is_excluded(&candidate, radius) {
+            candidate = Point::generate_rand_p(self.input_domain, &mut self.rng);
+            n_rejections += 1;
+        }
+
+        self.tcp.push(candidate.clone());
+        candidate
+    }
+
+    fn reset(&mut self) {
+        self.tcp.clear();
+    }
+
+    fn reseed(&mut self, seed: u64) {
+        self.rng = StdRng::seed_from_u64(seed);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_test_cases_outside_exclusion_zones() {
+        let input_domain = vec![vec![-5000, 5000], vec![-5000, 5000]];
+        let mut rrt = RrtArt::with_bound(&input_domain);
+        rrt.reseed(5);
+
+        for n_executed in 0..200 {
+            let radius = rrt.exclusion_radius(n_executed) as f32;
+            let p = rrt.next_test_case();
+            assert!(rrt.tcp[..n_executed]
+                .iter()
+                .all(|executed| Point::get_distance(&p, executed) >= radius));
+        }
+    }
+}
@@ -0,0 +1,644 @@
+pub mod checkpoint;
+pub mod effectiveness;
+pub mod efficiency;
+pub mod raw;
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use self::{effectiveness::EffectivenessConfig, efficiency::EfficiencyConfig};
+use crate::{
+    art::{
+        art_generator::ArtGenerator,
+        bisection_art::BisectionArt,
+        fscs_art::FscsArt,
+        kdfc_art::{BackNumSchedule, KdfcArt, KdfcVariant},
+        lhs_art::LhsArt,
+        quasi_random::{QuasiRandom, QuasiSequence, Scrambling, SOBOL_MAX_DIMS},
+        random_partition_art::RandomPartitionArt,
+        rrt_art::RrtArt,
+        rt::Rt,
+    },
+    fault::{
+        fault_zone::{FaultZone, FaultZoneError},
+        fault_zone_ball::FaultZoneBall,
+        fault_zone_block::FaultZoneBlock,
+        fault_zone_ellipsoid::FaultZoneEllipsoid,
+        fault_zone_point_square::{
+            FaultZonePointSquare, OverlapPolicy, PointPattern, SizeDistribution,
+        },
+        fault_zone_strip::FaultZoneStrip,
+    },
+};
+
+/// Names of all algorithms, as accepted by `Algorithm::from_str`
+pub const ALGORITHM_NAMES: &str = concat!(
+    "rt, lhs, fscs, naive-kdfc, semi-bal-kdfc, lim-bal-kdfc, approx-kdfc, rrt, b-art, rp-art, ",
+    "halton, sobol"
+);
+
+/// A test case generation algorithm together with its parameters.
+///
+/// In experiment configuration files an algorithm is given by its name and its parameters, e.g.
+/// `{ name = "fscs", cand_num = 20 }`. Omitted parameters take the defaults of `from_str`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "name", rename_all = "kebab-case")]
+pub enum Algorithm {
+    Rt,
+    Lhs {
+        /// Partitions per dimension, `None` for about 1000 hypercubes in total
+        #[serde(default)]
+        n_partitions: Option<usize>,
+        #[serde(default = "default_exhaustive")]
+        exhaustive: bool,
+    },
+    Fscs {
+        #[serde(default = "default_cand_num")]
+        cand_num: i32,
+    },
+    NaiveKdfc,
+    SemiBalKdfc,
+    LimBalKdfc {
+        #[serde(default)]
+        back_num: BackNumSchedule,
+    },
+    ApproxKdfc {
+        /// Relative error bound of the nearest neighbour distance
+        #[serde(default = "default_epsilon")]
+        epsilon: f64,
+    },
+    Rrt {
+        #[serde(default = "default_exclusion_ratio")]
+        exclusion_ratio: f64,
+    },
+    BArt,
+    RpArt,
+    Halton {
+        #[serde(default)]
+        scrambling: Scrambling,
+    },
+    Sobol {
+        #[serde(default)]
+        scrambling: Scrambling,
+    },
+}
+
+fn default_exhaustive() -> bool {
+    true
+}
+
+fn default_cand_num() -> i32 {
+    10
+}
+
+fn default_epsilon() -> f64 {
+    0.1
+}
+
+fn default_exclusion_ratio() -> f64 {
+    1.5
+}
+
+impl Algorithm {
+    /// Checks that the algorithm supports input domains of `n_dims` dimensions.
+    pub fn check_dims(&self, n_dims: usize) -> Result<(), String> {
+        match self {
+            Algorithm::Sobol { .. } if n_dims > SOBOL_MAX_DIMS => Err(format!(
+                "sobol supports at most {SOBOL_MAX_DIMS} dimensions, got {n_dims}"
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// Name of the algorithm in the command-line interface and in configuration files.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Algorithm::Rt => "rt",
+            Algorithm::Lhs { .. } => "lhs",
+            Algorithm::Fscs { .. } => "fscs",
+            Algorithm::NaiveKdfc => "naive-kdfc",
+            Algorithm::SemiBalKdfc => "semi-bal-kdfc",
+            Algorithm::LimBalKdfc { .. } => "lim-bal-kdfc",
+            Algorithm::ApproxKdfc { .. } => "approx-kdfc",
+            Algorithm::Rrt { .. } => "rrt",
+            Algorithm::BArt => "b-art",
+            Algorithm::RpArt => "rp-art",
+            Algorithm::Halton { .. } => "halton",
+            Algorithm::Sobol { .. } => "sobol",
+        }
+    }
+
+    /// Name of the algorithm in result files. Parameters that differ from the defaults are
+    /// appended to the name, so that the same algorithm with different parameters can be compared.
+    pub fn label(&self) -> String {
+        if Self::from_str(self.name()).as_ref() == Ok(self) {
+            return self.name().to_string();
+        }
+
+        let params = match self {
+            Algorithm::Lhs {
+                n_partitions,
+                exhaustive,
+            } => match n_partitions {
+                Some(n_partitions) => {
+                    format!("n_partitions={n_partitions};exhaustive={exhaustive}")
+                }
+                None => format!("exhaustive={exhaustive}"),
+            },
+            Algorithm::Fscs { cand_num } => format!("cand_num={cand_num}"),
+            Algorithm::LimBalKdfc { back_num } => match back_num {
+                BackNumSchedule::Logarithmic {
+                    coefficient: Some(coefficient),
+                } => format!("back_num=logarithmic:{coefficient}"),
+                BackNumSchedule::Logarithmic { coefficient: None } => {
+                    "back_num=logarithmic".to_string()
+                }
+                BackNumSchedule::Constant { back_num } => format!("back_num=constant:{back_num}"),
+            },
+            Algorithm::ApproxKdfc { epsilon } => format!("epsilon={epsilon}"),
+            Algorithm::Rrt { exclusion_ratio } => format!("exclusion_ratio={exclusion_ratio}"),
+            Algorithm::Halton { scrambling } | Algorithm::Sobol { scrambling } => {
+                let scrambling = match scrambling {
+                    Scrambling::None => "none",
+                    Scrambling::RandomShift => "random-shift",
+                    Scrambling::Owen => "owen",
+                };
+                format!("scrambling={scrambling}")
+            }
+            Algorithm::Rt
+            | Algorithm::NaiveKdfc
+            | Algorithm::SemiBalKdfc
+            | Algorithm::BArt
+            | Algorithm::RpArt => String::new(),
+        };
+        format!("{}({params})", self.name())
+    }
+
+    pub fn generator<'a>(&self, bound: &'a [Vec<i32>]) -> Box<dyn ArtGenerator + 'a> {
+        match *self {
+            Algorithm::Rt => Box::new(Rt::with_bound(bound)),
+            Algorithm::Lhs {
+                n_partitions,
+                exhaustive,
+            } => {
+                let mut lhs = LhsArt::with_bound(bound);
+                lhs.n_partitions = n_partitions
+                    .unwrap_or_else(|| 1000.0f64.powf(1.0 / bound.len() as f64) as usize);
+                lhs.exhaustive = exhaustive;
+                Box::new(lhs)
+            }
+            Algorithm::Fscs { cand_num } => {
+                let mut fscs = FscsArt::with_bound(bound);
+                fscs.cand_num = cand_num;
+                Box::new(fscs)
+            }
+            Algorithm::NaiveKdfc => Box::new(KdfcArt::with_variant(bound, KdfcVariant::Naive)),
+            Algorithm::SemiBalKdfc => {
+                Box::new(KdfcArt::with_variant(bound, KdfcVariant::SemiBalanced))
+            }
+            Algorithm::LimBalKdfc { back_num } => {
+                let mut kdfc = KdfcArt::with_variant(bound, KdfcVariant::LimitedBalanced);
+                kdfc.back_num_schedule = back_num;
+                Box::new(kdfc)
+            }
+            Algorithm::ApproxKdfc { epsilon } => {
+                let mut kdfc = KdfcArt::with_variant(bound, KdfcVariant::Approximate);
+                kdfc.epsilon = epsilon;
+                Box::new(kdfc)
+            }
+            Algorithm::Rrt { exclusion_ratio } => {
+                let mut rrt = RrtArt::with_bound(bound);
+                rrt.exclusion_ratio = exclusion_ratio;
+                Box::new(rrt)
+            }
+            Algorithm::BArt => Box::new(BisectionArt::with_bound(bound)),
+            Algorithm::RpArt => Box::new(RandomPartitionArt::with_bound(bound)),
+            Algorithm::Halton { scrambling } => {
+                let mut halton = QuasiRandom::with_sequence(bound, QuasiSequence::Halton);
+                halton.scrambling = scrambling;
+                Box::new(halton)
+            }
+            Algorithm::Sobol { scrambling } => {
+                let mut sobol = QuasiRandom::with_sequence(bound, QuasiSequence::Sobol);
+                sobol.scrambling = scrambling;
+                Box::new(sobol)
+            }
+        }
+    }
+}
+
+impl FromStr for Algorithm {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "rt" => Ok(Algorithm::Rt),
+            "lhs" => Ok(Algorithm::Lhs {
+                n_partitions: None,
+                exhaustive: default_exhaustive(),
+            }),
+            "fscs" => Ok(Algorithm::Fscs {
+                cand_num: default_cand_num(),
+            }),
+            "naive-kdfc" => Ok(Algorithm::NaiveKdfc),
+            "semi-bal-kdfc" => Ok(Algorithm::SemiBalKdfc),
+            "lim-bal-kdfc" => Ok(Algorithm::LimBalKdfc {
+                back_num: BackNumSchedule::default(),
+            }),
+            "approx-kdfc" => Ok(Algorithm::ApproxKdfc {
+                epsilon: default_epsilon(),
+            }),
+            "rrt" => Ok(Algorithm::Rrt {
+                exclusion_ratio: default_exclusion_ratio(),
+            }),
+            "b-art" => Ok(Algorithm::BArt),
+            "rp-art" => Ok(Algorithm::RpArt),
+            "halton" => Ok(Algorithm::Halton {
+                scrambling: Scrambling::default(),
+            }),
+            "sobol" => Ok(Algorithm::Sobol {
+                scrambling: Scrambling::default(),
+            }),
+            _ => Err(format!(
+                "unknown algorithm '{s}', expected one of {ALGORITHM_NAMES}"
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Algorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.label())
+    }
+}
+
+/// The shape of the fault zones in an effectiveness experiment.
+///
+/// In experiment configuration files a shape is given by its name and its parameters, e.g.
+/// `{ name = "strip", rate = 0.9 }` or
+/// `{ name = "point", pattern = { n_regions = 10, sizes = { distribution = "random-split" } } }`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "name", rename_all = "kebab-case")]
+pub enum Shape {
+    Block,
+    Strip {
+        /// Central fraction, in [0, 1], of the range of offsets across the input domain that the
+        /// strip centre is drawn from
+        #[serde(default = "default_strip_rate")]
+        rate: f64,
+    },
+    Point {
+        #[serde(default)]
+        pattern: PointPattern,
+    },
+    Ball,
+    Ellipsoid {
+        /// Length of the longest axis relative to the other axes
+        #[serde(default = "default_aspect_ratio")]
+        aspect_ratio: f64,
+        /// Whether the axes are rotated randomly instead of parallel to the coordinate axes
+        #[serde(default = "default_rotate")]
+        rotate: bool,
+    },
+}
+
+fn default_strip_rate() -> f64 {
+    0.9
+}
+
+fn default_aspect_ratio() -> f64 {
+    4.0
+}
+
+fn default_rotate() -> bool {
+    true
+}
+
+impl Shape {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Shape::Block => "block",
+            Shape::Strip { .. } => "strip",
+            Shape::Point { .. } => "point",
+            Shape::Ball => "ball",
+            Shape::Ellipsoid { .. } => "ellipsoid",
+        }
+    }
+
+    pub fn fault_zone<'a, R: Rng + ?Sized>(
+        &self,
+        bound: &'a [Vec<i32>],
+        theta: f64,
+        rng: &mut R,
+    ) -> Result<Box<dyn FaultZone + 'a>, FaultZoneError> {
+        Ok(match *self {
+            Shape::Block => Box::new(FaultZoneBlock::new(bound, theta, rng)?),
+            Shape::Strip { rate } => Box::new(FaultZoneStrip::new(bound, theta, rate, rng)?),
+            Shape::Point { pattern } => Box::new(FaultZonePointSquare::with_pattern(
+                bound, theta, &pattern, rng,
+            )?),
+            Shape::Ball => Box::new(FaultZoneBall::new(bound, theta, rng)?),
+            Shape::Ellipsoid {
+                aspect_ratio,
+                rotate,
+            } => Box::new(FaultZoneEllipsoid::new(
+                bound,
+                theta,
+                aspect_ratio,
+                rotate,
+                rng,
+            )?),
+        })
+    }
+}
+
+impl FromStr for Shape {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "block" => Ok(Shape::Block),
+            "strip" => Ok(Shape::Strip {
+                rate: default_strip_rate(),
+            }),
+            "point" => Ok(Shape::Point {
+                pattern: PointPattern::default(),
+            }),
+            "ball" => Ok(Shape::Ball),
+            "ellipsoid" => Ok(Shape::Ellipsoid {
+                aspect_ratio: default_aspect_ratio(),
+                rotate: default_rotate(),
+            }),
+            _ => Err(format!(
+                "unknown shape '{s}', expected one of block, strip, point, ball, ellipsoid"
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Shape {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Shape::Strip { rate } if *rate != default_strip_rate() => {
+                write!(f, "{}(rate={rate})", self.name())
+            }
+            Shape::Ellipsoid {
+                aspect_ratio,
+                rotate,
+            } if *aspect_ratio != default_aspect_ratio() || *rotate != default_rotate() => {
+                write!(
+                    f,
+                    "{}(aspect_ratio={aspect_ratio};rotate={rotate})",
+                    self.name()
+                )
+            }
+            Shape::Point { pattern } if *pattern != PointPattern::default() => {
+                let sizes = match pattern.sizes {
+                    SizeDistribution::Equal => "equal".to_string(),
+                    SizeDistribution::RandomSplit => "random-split".to_string(),
+                    SizeDistribution::PowerLaw { exponent } => format!("power-law:{exponent}"),
+                };
+                let overlap = match pattern.overlap {
+                    OverlapPolicy::Disjoint => "disjoint",
+                    OverlapPolicy::Allow => "allow",
+                };
+                write!(f, "{}(n_regions={};sizes={sizes}", self.name(), pattern.n_regions)?;
+                if let Some(spread) = pattern.cluster_spread {
+                    write!(f, ";cluster_spread={spread}")?;
+                }
+                write!(f, ";overlap={overlap})")
+            }
+            _ => f.write_str(self.name()),
+        }
+    }
+}
+
+/// The input domain of an experiment.
+///
+/// In experiment configuration files a domain is either the `[lower, upper]` bounds of each
+/// dimension, e.g. `[[-5000, 5000], [0, 100]]`, or a hypercube, e.g. `{ dims = 6 }` or
+/// `{ dims = 6, lower = 0, upper = 1000 }`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Domain {
+    Bounds(Vec<Vec<i32>>),
+    Cube {
+        dims: usize,
+        #[serde(default = "default_lower")]
+        lower: i32,
+        #[serde(default = "default_upper")]
+        upper: i32,
+    },
+}
+
+fn default_lower() -> i32 {
+    -5000
+}
+
+fn default_upper() -> i32 {
+    5000
+}
+
+impl Domain {
+    /// A hypercube of `dims` dimensions with the bounds [-5000, 5000].
+    pub fn cube(dims: usize) -> Self {
+        Domain::Cube {
+            dims,
+            lower: default_lower(),
+            upper: default_upper(),
+        }
+    }
+
+    pub fn bounds(&self) -> Vec<Vec<i32>> {
+        match self {
+            Domain::Bounds(bounds) => bounds.clone(),
+            Domain::Cube { dims, lower, upper } => vec![vec![*lower, *upper]; *dims],
+        }
+    }
+
+    /// Returns an error naming the domain if it has no dimensions, or a dimension without a lower
+    /// bound below its upper bound.
+    pub fn validate(&self) -> Result<(), String> {
+        let bounds = self.bounds();
+        let valid = !bounds.is_empty() && bounds.iter().all(|b| b.len() == 2 && b[0] < b[1]);
+        if valid {
+            Ok(())
+        } else {
+            let domain = serde_json::to_string(self).unwrap_or_default();
+            Err(format!(
+                "invalid domain {domain}, it needs at least one dimension and [lower, upper] \
+                 bounds with lower < upper in each"
+            ))
+        }
+    }
+}
+
+/// An experiment configuration file. It holds an effectiveness experiment, an efficiency
+/// experiment or both, e.g. in TOML:
+///
+/// ```toml
+/// [effectiveness]
+/// algorithms = [{ name = "rt" }, { name = "fscs", cand_num = 20 }]
+/// shapes = [{ name = "block" }, { name = "strip", rate = 0.8 }]
+/// area_sizes = [0.01, 0.001]
+/// domains = [{ dims = 2 }, [[-5000, 5000], [0, 1000], [0, 1000]]]
+/// n_iter = 100
+/// n_repeat_fault_zone = 10
+/// seed = 42
+/// ```
+///
+/// Omitted fields take the values of the original studies and a random seed.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ExperimentConfig {
+    pub effectiveness: Option<EffectivenessConfig>,
+    pub efficiency: Option<EfficiencyConfig>,
+}
+
+impl ExperimentConfig {
+    /// Reads a TOML or JSON configuration file, depending on the file extension.
+    pub fn from_file(path: &Path) -> std::io::Result<Self> {
+        let content = fs::read_to_string(path)?;
+        let config: Self = match path.extension().and_then(|e| e.to_str()) {
+            Some("json") => serde_json::from_str(&content).map_err(io::Error::other)?,
+            _ => toml::from_str(&content).map_err(io::Error::other)?,
+        };
+        let effectiveness_domains = config.effectiveness.iter().flat_map(|c| &c.domains);
+        let efficiency_domains = config.efficiency.iter().flat_map(|c| &c.domains);
+        for domain in effectiveness_domains.chain(efficiency_domains) {
+            domain
+                .validate()
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+        }
+        Ok(config)
+    }
+}
+
+/// Fails before an experiment starts if one of the domains is invalid or one of the algorithms does
+/// not support one of them.
+fn check_algorithms(algorithms: &[Algorithm], domains: &[Domain]) -> io::Result<()> {
+    for domain in domains {
+        domain
+            .validate()
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+        let n_dims = domain.bounds().len();
+        for algorithm in algorithms {
+            algorithm
+                .check_dims(n_dims)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+        }
+    }
+    Ok(())
+}
+
+/// Saves `config` as JSON into `dir` next to the results it produced, so that the results can be
+/// traced back to their exact configuration, including the seed.
+fn save_config<T: Serialize>(config: &T, dir: &Path, time: &str) -> io::Result<PathBuf> {
+    let path = dir.join(format!("config-{time}.json"));
+    let content = serde_json::to_string_pretty(config).map_err(io::Error::other)?;
+    fs::write(&path, content)?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_experiment_config() {
+        let config: ExperimentConfig = toml::from_str(
+            r#"
+            [effectiveness]
+            algorithms = [
+                { name = "rt" },
+                { name = "fscs", cand_num = 20 },
+                { name = "lim-bal-kdfc", back_num = { schedule = "constant", back_num = 8 } },
+                { name = "approx-kdfc", epsilon = 0.5 },
+            ]
+            shapes = [{ name = "strip", rate = 0.8 }]
+            domains = [{ dims = 2 }, [[0, 10], [-5, 5]]]
+            seed = 7
+            "#,
+        )
+        .unwrap();
+
+        assert!(config.efficiency.is_none());
+        let effectiveness = config.effectiveness.unwrap();
+        assert_eq!(
+            effectiveness.algorithms,
+            vec![
+                Algorithm::Rt,
+                Algorithm::Fscs { cand_num: 20 },
+                Algorithm::LimBalKdfc {
+                    back_num: BackNumSchedule::Constant { back_num: 8 }
+                },
+                Algorithm::ApproxKdfc { epsilon: 0.5 },
+            ]
+        );
+        assert_eq!(
+            effectiveness
+                .algorithms
+                .iter()
+                .map(|a| a.label())
+                .collect::<Vec<_>>(),
+            vec![
+                "rt",
+                "fscs(cand_num=20)",
+                "lim-bal-kdfc(back_num=constant:8)",
+                "approx-kdfc(epsilon=0.5)"
+            ]
+        );
+        assert_eq!(effectiveness.shapes, vec![Shape::Strip { rate: 0.8 }]);
+        assert_eq!(
+            effectiveness
+                .domains
+                .iter()
+                .map(|d| d.bounds())
+                .collect::<Vec<_>>(),
+            vec![
+                vec![vec![-5000, 5000], vec![-5000, 5000]],
+                vec![vec![0, 10], vec![-5, 5]]
+            ]
+        );
+        assert_eq!(effectiveness.seed, 7);
+        assert_eq!(effectiveness.n_iter, EffectivenessConfig::default().n_iter);
+    }
+
+    #[test]
+    fn test_check_algorithms() {
+        let algorithms = [Algorithm::Rt, Algorithm::from_str("sobol").unwrap()];
+        assert!(check_algorithms(&algorithms, &[Domain::cube(SOBOL_MAX_DIMS)]).is_ok());
+
+        let err = check_algorithms(&algorithms, &[Domain::cube(2), Domain::cube(25)]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        assert!(check_algorithms(&algorithms[..1], &[Domain::cube(25)]).is_ok());
+    }
+
+    #[test]
+    fn test_reject_invalid_domains() {
+        let path = std::env::temp_dir().join(format!("rart-domains-{}.toml", std::process::id()));
+        let invalid = [
+            "[[0, 10], [5]]",
+            "[[0, 10], [5, 5]]",
+            "[]",
+            "{ dims = 2, lower = 10, upper = 0 }",
+            "{ dims = 0 }",
+        ];
+        for domain in invalid {
+            fs::write(&path, format!("[efficiency]\ndomains = [{{ dims = 2 }}, {domain}]")).unwrap();
+            let err = ExperimentConfig::from_file(&path).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidInput, "{domain}");
+            assert!(err.to_string().starts_with("invalid domain"), "{err}");
+        }
+
+        fs::write(&path, "[efficiency]\ndomains = [{ dims = 2 }, [[0, 10], [-5, 5]]]").unwrap();
+        assert!(ExperimentConfig::from_file(&path).is_ok());
+        fs::remove_file(&path).unwrap();
+    }
+}
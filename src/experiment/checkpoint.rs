@@ -0,0 +1,223 @@
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::hash::{Hash, Hasher};
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+
+use crate::art::art_generator::TrialResult;
+
+/// Identifies a cell of an effectiveness experiment, i.e. one algorithm on one trial of one
+/// configuration.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CellKey {
+    /// Label of the algorithm, including its non-default parameters
+    pub algorithm: String,
+    /// Name of the shape, including its non-default parameters
+    pub shape: String,
+    pub theta: f64,
+    pub domain: Vec<Vec<i32>>,
+    /// Number of test cases of the P-measure and E-measure
+    pub n_tests: usize,
+    pub fault_zone: usize,
+    pub repetition: usize,
+}
+
+impl PartialEq for CellKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.algorithm == other.algorithm
+            && self.shape == other.shape
+            && self.theta.to_bits() == other.theta.to_bits()
+            && self.domain == other.domain
+            && self.n_tests == other.n_tests
+            && self.fault_zone == other.fault_zone
+            && self.repetition == other.repetition
+    }
+}
+
+impl Eq for CellKey {}
+
+impl Hash for CellKey {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.algorithm.hash(state);
+        self.shape.hash(state);
+        self.theta.to_bits().hash(state);
+        self.domain.hash(state);
+        self.n_tests.hash(state);
+        self.fault_zone.hash(state);
+        self.repetition.hash(state);
+    }
+}
+
+/// A completed cell with its result
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Cell {
+    #[serde(flatten)]
+    pub key: CellKey,
+    #[serde(flatten)]
+    pub result: TrialResult,
+    pub wall_time_ms: f64,
+}
+
+/// First line of a checkpoint file
+#[derive(Debug, Serialize, Deserialize)]
+struct Header {
+    seed: u64,
+}
+
+/// Completed cells of an effectiveness experiment, persisted as JSON Lines so that an interrupted
+/// experiment can be resumed.
+///
+/// The file starts with the master seed of the experiment, as the cells are only valid for it.
+/// Cells are appended and flushed as they are completed. A last line cut off by an interruption is
+/// dropped when the checkpoint is opened again.
+pub struct Checkpoint {
+    cells: HashMap<CellKey, Cell>,
+    file: Mutex<BufWriter<File>>,
+}
+
+impl Checkpoint {
+    /// Opens the checkpoint at `path`, or creates it if it does not exist.
+    ///
+    /// Fails if the checkpoint was written for a different master seed.
+    pub fn open(path: &Path, seed: u64) -> io::Result<Self> {
+        let mut cells = HashMap::new();
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(e),
+        };
+
+        // A last line without a line break was cut off while being written
+        let complete = &content[..content.rfind('\n').map_or(0, |i| i + 1)];
+        let mut lines = complete.lines();
+        if let Some(header) = lines.next() {
+            let header: Header = serde_json::from_str(header).map_err(io::Error::other)?;
+            if header.seed != seed {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!(
+                        "checkpoint {} was written with seed {}, not {seed}",
+                        path.display(),
+                        header.seed
+                    ),
+                ));
+            }
+        }
+        for line in lines {
+            let cell: Cell = serde_json::from_str(line).map_err(io::Error::other)?;
+            cells.insert(cell.key.clone(), cell);
+        }
+
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        file.set_len(complete.len() as u64)?;
+        let mut file = BufWriter::new(file);
+        if complete.is_empty() {
+            serde_json::to_writer(&mut file, &Header { seed }).map_err(io::Error::other)?;
+            writeln!(file)?;
+            file.flush()?;
+        }
+
+        Ok(Self {
+            cells,
+            file: Mutex::new(file),
+        })
+    }
+
+    /// Returns the master seed of the checkpoint at `path`, or `None` if it does not exist.
+    pub fn seed(path: &Path) -> io::Result<Option<u64>> {
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e),
+        };
+        match content.lines().next() {
+            Some(header) => {
+                let header: Header = serde_json::from_str(header).map_err(io::Error::other)?;
+                Ok(Some(header.seed))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Returns the completed cell with the key, if any.
+    pub fn get(&self, key: &CellKey) -> Option<&Cell> {
+        self.cells.get(key)
+    }
+
+    /// Returns the number of completed cells loaded from the file.
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// Appends completed cells to the file and flushes it. Can be called from several threads.
+    pub fn record(&self, cells: &[Cell]) -> io::Result<()> {
+        let mut file = self.file.lock().expect("checkpoint writer panicked");
+        for cell in cells {
+            serde_json::to_writer(&mut *file, cell).map_err(io::Error::other)?;
+            writeln!(file)?;
+        }
+        file.flush()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn cell(fault_zone: usize) -> Cell {
+        Cell {
+            key: CellKey {
+                algorithm: "fscs".to_string(),
+                shape: "block".to_string(),
+                theta: 0.01,
+                domain: vec![vec![0, 10], vec![-5, 5]],
+                n_tests: 100,
+                fault_zone,
+                repetition: 1,
+            },
+            result: TrialResult {
+                f_count: 42,
+                n_failures: 1,
+                max_tries_reached: false,
+            },
+            wall_time_ms: 0.5,
+        }
+    }
+
+    #[test]
+    fn test_checkpoint_resume() {
+        let path =
+            std::env::temp_dir().join(format!("rart-checkpoint-{}.jsonl", std::process::id()));
+        let _ = fs::remove_file(&path);
+
+        let checkpoint = Checkpoint::open(&path, 7).unwrap();
+        assert!(checkpoint.is_empty());
+        checkpoint.record(&[cell(0), cell(1)]).unwrap();
+        drop(checkpoint);
+
+        // An interruption while writing leaves a partial last line
+        let mut content = fs::read_to_string(&path).unwrap();
+        content.push_str(r#"{"algorithm":"fscs","sha"#);
+        fs::write(&path, content).unwrap();
+
+        assert_eq!(Checkpoint::seed(&path).unwrap(), Some(7));
+        assert!(Checkpoint::open(&path, 8).is_err());
+        let checkpoint = Checkpoint::open(&path, 7).unwrap();
+        assert_eq!(checkpoint.len(), 2);
+        assert_eq!(checkpoint.get(&cell(1).key), Some(&cell(1)));
+        assert_eq!(checkpoint.get(&cell(2).key), None);
+        checkpoint.record(&[cell(2)]).unwrap();
+        drop(checkpoint);
+
+        let checkpoint = Checkpoint::open(&path, 7).unwrap();
+        assert_eq!(checkpoint.len(), 3);
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,947 @@
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use serde::{Deserialize, Serialize};
+
+use crate::art::art_generator::TrialResult;
+use crate::fault::{
+    fault_zone::{measure_theta, sample_theta, FaultZone, FaultZoneError, N_THETA_SAMPLES},
+    fault_zone_point_square::PointPattern,
+};
+use crate::util::{
+    seed::derive_seed,
+    stats::{holm_correction, mann_whitney_u, vargha_delaney_a12, Summary},
+};
+
+use super::{
+    check_algorithms,
+    checkpoint::{Cell, CellKey, Checkpoint},
+    raw::{csv_field, RawFormat, RawRecord, RawWriter},
+    save_config, Algorithm, Domain, Shape,
+};
+
+/// Parameters of an effectiveness experiment, i.e. how many test cases each algorithm needs to
+/// find a fault zone.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct EffectivenessConfig {
+    pub algorithms: Vec<Algorithm>,
+    pub shapes: Vec<Shape>,
+    /// Failure rates, i.e. fault zone sizes relative to the input domain
+    pub area_sizes: Vec<f64>,
+    pub domains: Vec<Domain>,
+    /// Number of fault zones generated per configuration
+    pub n_iter: usize,
+    /// Number of runs per algorithm on each fault zone
+    pub n_repeat_fault_zone: usize,
+    /// Master seed of all fault zones and generators
+    pub seed: u64,
+    pub output_dir: PathBuf,
+    /// Number of worker threads, `None` for the available parallelism
+    pub n_threads: Option<usize>,
+    /// If set, the failure rate of every fault zone is measured and the experiment fails if it
+    /// deviates from the nominal rate by more than this relative tolerance, beyond the confidence
+    /// interval of the measurement
+    pub validate_theta: Option<f64>,
+    /// Number of test cases of the P-measure and E-measure, `None` for `1 / theta` rounded, the
+    /// expected F-measure of random testing
+    pub n_tests: Option<usize>,
+    /// Format of the raw results file with one record per trial and algorithm
+    pub raw_format: RawFormat,
+    /// File in which completed trials are saved as they finish. Running the experiment again with
+    /// the same checkpoint and seed skips them, so that an interrupted experiment can be resumed.
+    pub checkpoint: Option<PathBuf>,
+}
+
+impl Default for EffectivenessConfig {
+    /// The configuration of the original effectiveness study with a random seed.
+    fn default() -> Self {
+        Self {
+            algorithms: vec![
+                Algorithm::Rt,
+                Algorithm::Lhs {
+                    n_partitions: None,
+                    exhaustive: true,
+                },
+            ],
+            shapes: vec![
+                Shape::Block,
+                Shape::Strip { rate: 0.9 },
+                Shape::Point {
+                    pattern: PointPattern::default(),
+                },
+            ],
+            area_sizes: vec![0.01, 0.005, 0.002, 0.001, 0.0005, 0.0002, 0.0001],
+            domains: vec![Domain::cube(6)],
+            n_iter: 1000,
+            n_repeat_fault_zone: 10,
+            seed: rand::random(),
+            output_dir: PathBuf::from("test-results/effectiveness"),
+            n_threads: None,
+            validate_theta: None,
+            n_tests: None,
+            raw_format: RawFormat::default(),
+            checkpoint: None,
+        }
+    }
+}
+
+impl EffectivenessConfig {
+    fn threads(&self) -> usize {
+        self.n_threads
+            .unwrap_or_else(|| thread::available_parallelism().map_or(1, |n| n.get()))
+            .max(1)
+    }
+}
+
+/// Runs all configurations of an effectiveness experiment and writes the results into a summary
+/// CSV in the output directory, next to a copy of the configuration, a comparison CSV with the
+/// pairwise significance tests of the algorithms and the raw results of every trial.
+///
+/// Returns the path of the summary CSV.
+pub fn run_effectiveness(config: &EffectivenessConfig) -> std::io::Result<PathBuf> {
+    check_algorithms(&config.algorithms, &config.domains)?;
+    if let Some(area_size) = config.area_sizes.iter().find(|&&a| !(a > 0.0 && a <= 1.0)) {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!("area size {area_size} is not in (0, 1]"),
+        ));
+    }
+    let cur_time = chrono::Local::now().format("%Y-%m-%d_%H-%M-%S");
+    fs::create_dir_all(&config.output_dir)?;
+    let summary_path = config.output_dir.join(format!("summary-{}.csv", cur_time));
+    save_config(config, &config.output_dir, &cur_time.to_string())?;
+    let result_file_summary = File::create(&summary_path)?;
+    let mut result_csv_writer = ResultCsvWriter::new(&result_file_summary)?;
+    result_csv_writer.init()?;
+    let comparison_file = File::create(
+        config
+            .output_dir
+            .join(format!("comparison-{}.csv", cur_time)),
+    )?;
+    let mut comparison_csv_writer = ComparisonCsvWriter::new(&comparison_file, &config.algorithms)?;
+    comparison_csv_writer.init()?;
+    let raw_writer =
+        RawWriter::create(&config.output_dir, &cur_time.to_string(), config.raw_format)?;
+    let mut writers = Writers {
+        summary: result_csv_writer,
+        comparison: comparison_csv_writer,
+        raw: raw_writer,
+    };
+
+    let checkpoint = config
+        .checkpoint
+        .as_deref()
+        .map(|path| Checkpoint::open(path, config.seed))
+        .transpose()?;
+
+    println!("seed: {}", config.seed);
+    if let Some(checkpoint) = checkpoint.as_ref().filter(|c| !c.is_empty()) {
+        println!("resuming with {} completed trials", checkpoint.len());
+    }
+
+    for domain in &config.domains {
+        let space_bounds = domain.bounds();
+
+        for &area_size in &config.area_sizes {
+            println!("{}d {area_size}", space_bounds.len());
+
+            for shape in &config.shapes {
+                println!("{shape}");
+                fix_rate_test(
+                    &mut writers,
+                    checkpoint.as_ref(),
+                    config,
+                    area_size,
+                    &space_bounds,
+                    shape,
+                )?;
+            }
+        }
+    }
+
+    Ok(summary_path)
+}
+
+fn fix_rate_test(
+    writers: &mut Writers,
+    checkpoint: Option<&Checkpoint>,
+    config: &EffectivenessConfig,
+    area_size: f64,
+    space_bounds: &[Vec<i32>],
+    shape: &Shape,
+) -> std::io::Result<()> {
+    let n_tests = config
+        .n_tests
+        .unwrap_or_else(|| (1.0 / area_size).round() as usize);
+    let trials = run_trials(config, area_size, space_bounds, shape, n_tests, checkpoint)?;
+
+    let labels = config
+        .algorithms
+        .iter()
+        .map(|a| a.label())
+        .collect::<Vec<_>>();
+    let width = label_width(&labels);
+    let shape_name = shape.to_string();
+    for (t, trial) in trials.iter().enumerate() {
+        for (a, algorithm) in config.algorithms.iter().enumerate() {
+            writers.raw.write(&EffectivenessRecord {
+                algorithm: labels[a].clone(),
+                parameters: *algorithm,
+                shape: shape_name.clone(),
+                theta: area_size,
+                space_dim: space_bounds.len(),
+                trial: t,
+                fault_zone: trial.fault_zone,
+                fault_zone_seed: trial.fault_zone_seed,
+                repetition: trial.repetition,
+                f_count: trial.results[a].f_count,
+                n_failures: trial.results[a].n_failures,
+                wall_time_ms: trial.wall_times[a].as_secs_f64() * 1e3,
+                max_tries_reached: trial.results[a].max_tries_reached,
+            })?;
+        }
+    }
+    writers.raw.flush()?;
+
+    let mut f_counts = Vec::with_capacity(config.algorithms.len());
+    for (a, label) in labels.iter().enumerate() {
+        let results = trials
+            .iter()
+            .map(|trial| trial.results[a])
+            .collect::<Vec<_>>();
+        let wall_times = trials
+            .iter()
+            .map(|trial| trial.wall_times[a])
+            .collect::<Vec<_>>();
+        f_counts.push(results.iter().map(|r| r.f_count as f64).collect::<Vec<_>>());
+        let measures = Measures::of(&results, &wall_times, area_size, n_tests);
+        println!(
+            "  {:width$}F-ratio {:.2}%  F {:.2} [{:.2}, {:.2}]  P {:.4}  E {:.4}  {:.3} ms",
+            label,
+            measures.f_ratio,
+            measures.f_measure.mean,
+            measures.f_measure.lower,
+            measures.f_measure.upper,
+            measures.p_measure.mean,
+            measures.e_measure.mean,
+            measures.wall_time_ms.mean,
+        );
+        writers.summary.write(
+            label,
+            &shape_name,
+            area_size,
+            space_bounds.len() as u32,
+            config.seed,
+            &measures,
+        )?;
+    }
+    let n_cut_off = trials
+        .iter()
+        .flat_map(|trial| &trial.results)
+        .filter(|r| r.max_tries_reached)
+        .count();
+    if n_cut_off > 0 {
+        println!(
+            "  max tries reached in {n_cut_off} of {} runs",
+            trials.len() * config.algorithms.len()
+        );
+    }
+
+    writers.summary.buf.flush()?;
+
+    if config.algorithms.len() > 1 {
+        let comparison = Comparison::of(&f_counts);
+        comparison.print(&config.algorithms);
+        writers.comparison.write(
+            &shape_name,
+            area_size,
+            space_bounds.len() as u32,
+            config.seed,
+            &comparison,
+        )?;
+        writers.comparison.buf.flush()?;
+    }
+    Ok(())
+}
+
+/// Pairwise comparison of the F-measures of the algorithms of one configuration
+#[derive(Debug, Clone, PartialEq)]
+struct Comparison {
+    /// Mann-Whitney U p-values of each pair of algorithms, Holm-corrected over all pairs
+    p_values: Vec<Vec<f64>>,
+    /// Vargha-Delaney Â12 of the row algorithm against the column algorithm. Below 0.5 the row
+    /// algorithm tends to need fewer test cases.
+    a12: Vec<Vec<f64>>,
+}
+
+impl Comparison {
+    fn of(f_counts: &[Vec<f64>]) -> Self {
+        let n = f_counts.len();
+        let pairs = (0..n)
+            .flat_map(|i| (i + 1..n).map(move |j| (i, j)))
+            .collect::<Vec<_>>();
+        let raw_p_values = pairs
+            .iter()
+            .map(|&(i, j)| mann_whitney_u(&f_counts[i], &f_counts[j]).1)
+            .collect::<Vec<_>>();
+
+        let mut p_values = vec![vec![1.0; n]; n];
+        let mut a12 = vec![vec![0.5; n]; n];
+        for (&(i, j), p) in pairs.iter().zip(holm_correction(&raw_p_values)) {
+            (p_values[i][j], p_values[j][i]) = (p, p);
+            a12[i][j] = vargha_delaney_a12(&f_counts[i], &f_counts[j]);
+            a12[j][i] = 1.0 - a12[i][j];
+        }
+        Self { p_values, a12 }
+    }
+
+    /// Prints a table with the Â12 and p-value of each row algorithm against each column
+    /// algorithm.
+    fn print(&self, algorithms: &[Algorithm]) {
+        let labels = algorithms.iter().map(|a| a.label()).collect::<Vec<_>>();
+        let width = label_width(&labels);
+        let header = labels
+            .iter()
+            .map(|label| format!("{label:width$}"))
+            .collect::<String>();
+        println!("  {:width$}{}", "Â12 (Holm p)", header.trim_end());
+        for (i, label) in labels.iter().enumerate() {
+            let row = (0..labels.len())
+                .map(|j| {
+                    let cell = if i == j {
+                        "-".to_string()
+                    } else if self.p_values[i][j] < 0.001 {
+                        format!("{:.2} (<0.001)", self.a12[i][j])
+                    } else {
+                        format!("{:.2} ({:.3})", self.a12[i][j], self.p_values[i][j])
+                    };
+                    format!("{cell:width$}")
+                })
+                .collect::<String>();
+            println!("  {label:width$}{}", row.trim_end());
+        }
+    }
+}
+
+/// Returns the width of a console column that fits all labels, at least 20 characters.
+fn label_width(labels: &[String]) -> usize {
+    labels
+        .iter()
+        .map(|label| label.chars().count() + 2)
+        .fold(20, usize::max)
+}
+
+/// Effectiveness measures of an algorithm over all trials of one configuration
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Measures {
+    n_trials: usize,
+    /// Number of test cases until the first failure
+    f_measure: Summary,
+    /// Mean F-measure relative to the expected F-measure of random testing, `1 / theta`, in
+    /// percent
+    f_ratio: f64,
+    /// Number of test cases of the P-measure and E-measure
+    n_tests: usize,
+    /// Probability of at least one failure in `n_tests` test cases
+    p_measure: Summary,
+    /// Number of failures in `n_tests` test cases
+    e_measure: Summary,
+    /// Wall time of a trial in milliseconds, including the test cases generated after the first
+    /// failure to reach `n_tests`
+    wall_time_ms: Summary,
+}
+
+impl Measures {
+    fn of(
+        results: &[TrialResult],
+        wall_times: &[Duration],
+        area_size: f64,
+        n_tests: usize,
+    ) -> Self {
+        let f_counts = results.iter().map(|r| r.f_count as f64).collect::<Vec<_>>();
+        let n_failures = results
+            .iter()
+            .map(|r| r.n_failures as f64)
+            .collect::<Vec<_>>();
+        let n_detected = results.iter().filter(|r| r.n_failures > 0).count();
+        let wall_times_ms = wall_times
+            .iter()
+            .map(|t| t.as_secs_f64() * 1e3)
+            .collect::<Vec<_>>();
+
+        let f_measure = Summary::of(&f_counts);
+        Self {
+            n_trials: results.len(),
+            f_measure,
+            f_ratio: f_measure.mean * area_size * 100.0,
+            n_tests,
+            p_measure: Summary::of_proportion(n_detected, results.len()),
+            e_measure: Summary::of(&n_failures),
+            wall_time_ms: Summary::of(&wall_times_ms),
+        }
+    }
+}
+
+/// Results of all algorithms on one trial, i.e. one repetition on one fault zone
+#[derive(Debug, Clone)]
+struct Trial {
+    fault_zone: usize,
+    fault_zone_seed: u64,
+    repetition: usize,
+    results: Vec<TrialResult>,
+    /// Wall time of each algorithm on the trial
+    wall_times: Vec<Duration>,
+}
+
+/// Derives the seed of one configuration from the master `seed`, so that the fault zones of
+/// different configurations are not generated from the same random number streams.
+fn config_seed(seed: u64, shape: &str, area_size: f64, space_bounds: &[Vec<i32>]) -> u64 {
+    let seed = shape
+        .bytes()
+        .fold(seed, |seed, byte| derive_seed(seed, byte as u64));
+    let seed = derive_seed(seed, area_size.to_bits());
+    space_bounds
+        .iter()
+        .flatten()
+        .fold(seed, |seed, &bound| derive_seed(seed, bound as i64 as u64))
+}
+
+/// Runs all trials of one configuration in parallel and returns them ordered by fault zone and
+/// repetition, with one result per algorithm. Each algorithm runs for at least `n_tests` test cases
+/// per trial.
+///
+/// Fault zone i is generated with seed derive_seed(config_seed, i), where `config_seed` is
+/// derived from the master seed, the shape, `area_size` and the input domain.
+/// All generators run repetition j on it with seed derive_seed(derive_seed(config_seed, i), j),
+/// so any trial can be replayed alone, the counts are the same for any number of threads, and
+/// different configurations get independent fault zones.
+///
+/// With a checkpoint, fault zones whose trials are all completed in it are skipped, and the trials
+/// of the other fault zones are saved into it as soon as they finish.
+///
+/// Fails if a fault zone cannot be generated, e.g. because it does not fit into the input domain,
+/// if validation is on and its measured failure rate deviates from `area_size`, or if the
+/// checkpoint cannot be written.
+fn run_trials(
+    config: &EffectivenessConfig,
+    area_size: f64,
+    space_bounds: &[Vec<i32>],
+    shape: &Shape,
+    n_tests: usize,
+    checkpoint: Option<&Checkpoint>,
+) -> std::io::Result<Vec<Trial>> {
+    let next_fault_zone = AtomicUsize::new(0);
+    let labels = config
+        .algorithms
+        .iter()
+        .map(|a| a.label())
+        .collect::<Vec<_>>();
+    let shape_name = shape.to_string();
+    let config_seed = config_seed(config.seed, &shape_name, area_size, space_bounds);
+    let cell_key = |label: &str, fault_zone: usize, repetition: usize| CellKey {
+        algorithm: label.to_string(),
+        shape: shape_name.clone(),
+        theta: area_size,
+        domain: space_bounds.to_vec(),
+        n_tests,
+        fault_zone,
+        repetition,
+    };
+
+    let mut fault_zone_trials = thread::scope(|scope| {
+        let workers = (0..config.threads())
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut generators = config
+                        .algorithms
+                        .iter()
+                        .map(|algorithm| algorithm.generator(space_bounds))
+                        .collect::<Vec<_>>();
+                    let mut fault_zone_trials = Vec::new();
+                    // Stops the other workers too
+                    let stop = |e| {
+                        next_fault_zone.store(config.n_iter, Ordering::Relaxed);
+                        Err(e)
+                    };
+
+                    loop {
+                        let i = next_fault_zone.fetch_add(1, Ordering::Relaxed);
+                        if i >= config.n_iter {
+                            break;
+                        }
+
+                        let fault_zone_seed = derive_seed(config_seed, i as u64);
+                        if let Some(trials) = checkpoint.and_then(|checkpoint| {
+                            stored_trials(
+                                checkpoint,
+                                &labels,
+                                config.n_repeat_fault_zone,
+                                (i, fault_zone_seed),
+                                &cell_key,
+                            )
+                        }) {
+                            fault_zone_trials.push((i, trials));
+                            continue;
+                        }
+
+                        let mut rng = StdRng::seed_from_u64(fault_zone_seed);
+                        let fzb = match generate_fault_zone(
+                            config,
+                            area_size,
+                            space_bounds,
+                            shape,
+                            &mut rng,
+                        ) {
+                            Ok(fzb) => fzb,
+                            Err(e) => {
+                                return stop(std::io::Error::new(
+                                    std::io::ErrorKind::InvalidInput,
+                                    e,
+                                ))
+                            }
+                        };
+
+                        let trials = (0..config.n_repeat_fault_zone)
+                            .map(|j| {
+                                let trial_seed = derive_seed(fault_zone_seed, j as u64);
+                                let (results, wall_times) = generators
+                                    .iter_mut()
+                                    .map(|generator| {
+                                        let start = Instant::now();
+                                        generator.reseed(trial_seed);
+                                        let result = generator.test_measures(fzb.as_ref(), n_tests);
+                                        (result, start.elapsed())
+                                    })
+                                    .unzip();
+                                Trial {
+                                    fault_zone: i,
+                                    fault_zone_seed,
+                                    repetition: j,
+                                    results,
+                                    wall_times,
+                                }
+                            })
+                            .collect::<Vec<_>>();
+
+                        if let Some(checkpoint) = checkpoint {
+                            let cells = trials
+                                .iter()
+                                .flat_map(|trial| {
+                                    labels.iter().enumerate().map(|(a, label)| Cell {
+                                        key: cell_key(label, i, trial.repetition),
+                                        result: trial.results[a],
+                                        wall_time_ms: trial.wall_times[a].as_secs_f64() * 1e3,
+                                    })
+                                })
+                                .collect::<Vec<_>>();
+                            if let Err(e) = checkpoint.record(&cells) {
+                                return stop(e);
+                            }
+                        }
+                        fault_zone_trials.push((i, trials));
+                    }
+                    Ok(fault_zone_trials)
+                })
+            })
+            .collect::<Vec<_>>();
+
+        workers
+            .into_iter()
+            .map(|worker| worker.join().expect("effectiveness worker panicked"))
+            .collect::<Result<Vec<_>, _>>()
+    })?
+    .concat();
+
+    fault_zone_trials.sort_by_key(|(i, _)| *i);
+    Ok(fault_zone_trials
+        .into_iter()
+        .flat_map(|(_, trials)| trials)
+        .collect())
+}
+
+/// Returns the trials of a fault zone, given by its index and seed, from the checkpoint if all of
+/// them are completed there.
+fn stored_trials(
+    checkpoint: &Checkpoint,
+    labels: &[String],
+    n_repeat_fault_zone: usize,
+    (fault_zone, fault_zone_seed): (usize, u64),
+    cell_key: &impl Fn(&str, usize, usize) -> CellKey,
+) -> Option<Vec<Trial>> {
+    (0..n_repeat_fault_zone)
+        .map(|repetition| {
+            let (results, wall_times) = labels
+                .iter()
+                .map(|label| {
+                    let cell = checkpoint.get(&cell_key(label, fault_zone, repetition))?;
+                    Some((
+                        cell.result,
+                        Duration::from_secs_f64(cell.wall_time_ms / 1e3),
+                    ))
+                })
+                .collect::<Option<Vec<_>>>()?
+                .into_iter()
+                .unzip();
+            Some(Trial {
+                fault_zone,
+                fault_zone_seed,
+                repetition,
+                results,
+                wall_times,
+            })
+        })
+        .collect()
+}
+
+/// Generates a fault zone and, if validation is on, checks its measured failure rate.
+fn generate_fault_zone<'a>(
+    config: &EffectivenessConfig,
+    area_size: f64,
+    space_bounds: &'a [Vec<i32>],
+    shape: &Shape,
+    rng: &mut StdRng,
+) -> Result<Box<dyn FaultZone + 'a>, FaultZoneError> {
+    let fzb = shape.fault_zone(space_bounds, area_size, rng)?;
+    let theta = fzb.get_theta();
+    if !(theta > 0.0 && theta.is_finite()) {
+        return Err(FaultZoneError::InvalidTheta { theta });
+    }
+
+    if let Some(tolerance) = config.validate_theta {
+        let measured = measure_theta(fzb.as_ref(), space_bounds, N_THETA_SAMPLES, rng);
+        // An exact rate comes from the geometry the fault zone was sized with, so sampling checks
+        // that `find_target` hits the same region.
+        let sampled = measured
+            .is_exact()
+            .then(|| sample_theta(fzb.as_ref(), space_bounds, N_THETA_SAMPLES, rng));
+        for measured in std::iter::once(measured).chain(sampled) {
+            if !measured.agrees_with(area_size, tolerance) {
+                return Err(FaultZoneError::ThetaMismatch {
+                    nominal: area_size,
+                    measured,
+                });
+            }
+        }
+    }
+    Ok(fzb)
+}
+
+/// Output files of an effectiveness experiment
+struct Writers<'this> {
+    summary: ResultCsvWriter<'this>,
+    comparison: ComparisonCsvWriter<'this>,
+    raw: RawWriter<EffectivenessRecord>,
+}
+
+/// Raw result of one algorithm on one trial
+#[derive(Debug, Clone, Serialize)]
+pub struct EffectivenessRecord {
+    pub algorithm: String,
+    pub parameters: Algorithm,
+    pub shape: String,
+    pub theta: f64,
+    pub space_dim: usize,
+    /// Index of the trial in its configuration
+    pub trial: usize,
+    pub fault_zone: usize,
+    pub fault_zone_seed: u64,
+    /// The generator of the trial is seeded with `derive_seed(fault_zone_seed, repetition)`
+    pub repetition: usize,
+    pub f_count: usize,
+    pub n_failures: usize,
+    /// Wall time of `ArtGenerator::test_measures`, which covers at least `n_tests` test cases
+    pub wall_time_ms: f64,
+    pub max_tries_reached: bool,
+}
+
+impl RawRecord for EffectivenessRecord {
+    fn csv_header() -> &'static str {
+        "algorithm,parameters,shape,theta,space_dim,trial,fault_zone,fault_zone_seed,repetition,\
+         f_count,n_failures,wall_time_ms,max_tries_reached"
+    }
+
+    fn csv_row(&self) -> String {
+        let parameters = serde_json::to_string(&self.parameters).unwrap_or_default();
+        format!(
+            "{},{},{},{},{},{},{},{},{},{},{},{},{}",
+            csv_field(&self.algorithm),
+            csv_field(&parameters),
+            csv_field(&self.shape),
+            self.theta,
+            self.space_dim,
+            self.trial,
+            self.fault_zone,
+            self.fault_zone_seed,
+            self.repetition,
+            self.f_count,
+            self.n_failures,
+            self.wall_time_ms,
+            self.max_tries_reached,
+        )
+    }
+}
+
+struct ResultCsvWriter<'this> {
+    buf: BufWriter<&'this File>,
+}
+
+impl<'this> ResultCsvWriter<'this> {
+    fn new(file: &'this File) -> std::io::Result<Self> {
+        let buf = BufWriter::new(file);
+        Ok(Self { buf })
+    }
+
+    fn init(&mut self) -> std::io::Result<()> {
+        writeln!(
+            self.buf,
+            "algorithm,shape,area_size,space_dim,seed,n_trials,\
+             f_mean,f_sd,f_ci_lower,f_ci_upper,f_ratio,\
+             n_tests,p_measure,p_sd,p_ci_lower,p_ci_upper,\
+             e_mean,e_sd,e_ci_lower,e_ci_upper,\
+             time_ms_mean,time_ms_sd,time_ms_ci_lower,time_ms_ci_upper"
+        )?;
+        Ok(())
+    }
+
+    fn write(
+        &mut self,
+        algorithm: &str,
+        shape: &str,
+        area_size: f64,
+        space_dim: u32,
+        seed: u64,
+        measures: &Measures,
+    ) -> std::io::Result<()> {
+        let Measures {
+            n_trials,
+            f_measure: f,
+            f_ratio,
+            n_tests,
+            p_measure: p,
+            e_measure: e,
+            wall_time_ms: t,
+        } = measures;
+        // Mean, standard deviation and confidence interval of a measure
+        let summary = |m: &Summary| format!("{},{},{},{}", m.mean, m.sd, m.lower, m.upper);
+        writeln!(
+            self.buf,
+            "{algorithm},{shape},{area_size},{space_dim},{seed},{n_trials},{},{f_ratio},{n_tests},{},{},{}",
+            summary(f),
+            summary(p),
+            summary(e),
+            summary(t),
+        )?;
+        Ok(())
+    }
+}
+
+/// Writes the comparisons of all configurations as matrices, one row per statistic and row
+/// algorithm with a column per column algorithm.
+struct ComparisonCsvWriter<'this> {
+    buf: BufWriter<&'this File>,
+    labels: Vec<String>,
+}
+
+impl<'this> ComparisonCsvWriter<'this> {
+    fn new(file: &'this File, algorithms: &[Algorithm]) -> std::io::Result<Self> {
+        let buf = BufWriter::new(file);
+        let labels = algorithms.iter().map(|a| a.label()).collect();
+        Ok(Self { buf, labels })
+    }
+
+    fn init(&mut self) -> std::io::Result<()> {
+        writeln!(
+            self.buf,
+            "shape,area_size,space_dim,seed,statistic,algorithm,{}",
+            self.labels.join(",")
+        )?;
+        Ok(())
+    }
+
+    fn write(
+        &mut self,
+        shape: &str,
+        area_size: f64,
+        space_dim: u32,
+        seed: u64,
+        comparison: &Comparison,
+    ) -> std::io::Result<()> {
+        for (statistic, matrix) in [("p_holm", &comparison.p_values), ("a12", &comparison.a12)] {
+            for (label, row) in self.labels.iter().zip(matrix) {
+                let values = row.iter().map(|v| v.to_string()).collect::<Vec<_>>();
+                writeln!(
+                    self.buf,
+                    "{shape},{area_size},{space_dim},{seed},{statistic},{label},{}",
+                    values.join(",")
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_run_trials_independent_of_thread_count() {
+        let space_bounds = Domain::cube(2).bounds();
+        let mut config = EffectivenessConfig {
+            algorithms: vec![Algorithm::Rt, Algorithm::Fscs { cand_num: 10 }],
+            n_iter: 6,
+            n_repeat_fault_zone: 2,
+            seed: 3,
+            ..Default::default()
+        };
+
+        config.n_threads = Some(1);
+        let serial = run_trials(&config, 0.01, &space_bounds, &Shape::Block, 100, None).unwrap();
+        config.n_threads = Some(4);
+        let parallel = run_trials(&config, 0.01, &space_bounds, &Shape::Block, 100, None).unwrap();
+
+        assert_eq!(serial.len(), 12);
+        assert!(serial.iter().all(|trial| trial.results.len() == 2));
+        assert!(serial
+            .iter()
+            .zip(&parallel)
+            .all(|(s, p)| s.fault_zone == p.fault_zone
+                && s.repetition == p.repetition
+                && s.results == p.results));
+    }
+
+    #[test]
+    fn test_configurations_get_independent_seeds() {
+        let space_bounds = Domain::cube(2).bounds();
+        let config = EffectivenessConfig {
+            algorithms: vec![Algorithm::Rt],
+            n_iter: 3,
+            n_repeat_fault_zone: 1,
+            seed: 3,
+            ..Default::default()
+        };
+        let fault_zone_seeds = |area_size, shape: &Shape, space_bounds: &[Vec<i32>]| {
+            run_trials(&config, area_size, space_bounds, shape, 10, None)
+                .unwrap()
+                .iter()
+                .map(|trial| trial.fault_zone_seed)
+                .collect::<Vec<_>>()
+        };
+
+        let seeds = fault_zone_seeds(0.01, &Shape::Block, &space_bounds);
+        assert_eq!(seeds, fault_zone_seeds(0.01, &Shape::Block, &space_bounds));
+        let others = [
+            fault_zone_seeds(0.02, &Shape::Block, &space_bounds),
+            fault_zone_seeds(0.01, &Shape::Ball, &space_bounds),
+            fault_zone_seeds(0.01, &Shape::Block, &Domain::cube(3).bounds()),
+        ];
+        for other in others {
+            assert!(seeds.iter().all(|seed| !other.contains(seed)));
+        }
+    }
+
+    #[test]
+    fn test_measures() {
+        let results =
+            [(50, 0), (150, 0), (80, 1), (20, 3)].map(|(f_count, n_failures)| TrialResult {
+                f_count,
+                n_failures,
+                max_tries_reached: false,
+            });
+        let wall_times = [3, 5, 4, 4].map(Duration::from_millis);
+        let measures = Measures::of(&results, &wall_times, 0.01, 100);
+
+        assert_eq!(measures.n_trials, 4);
+        assert_eq!(measures.f_measure.mean, 75.0);
+        assert!((measures.f_measure.sd - 55.678).abs() < 1e-3);
+        assert!(measures.f_measure.lower < 75.0 && measures.f_measure.upper > 75.0);
+        assert_eq!(measures.f_ratio, 75.0);
+        assert_eq!(measures.p_measure.mean, 0.5);
+        assert!(measures.p_measure.lower > 0.0 && measures.p_measure.upper < 1.0);
+        assert_eq!(measures.e_measure.mean, 1.0);
+        assert_eq!(measures.wall_time_ms.mean, 4.0);
+    }
+
+    #[test]
+    fn test_comparison() {
+        let fast = (0..30).map(|i| i as f64).collect::<Vec<_>>();
+        let slow = (0..30).map(|i| i as f64 + 20.0).collect::<Vec<_>>();
+        let comparison = Comparison::of(&[fast.clone(), slow, fast]);
+
+        assert!(comparison.p_values[0][1] < 0.001);
+        assert!(comparison.p_values[0][2] > 0.99);
+        assert_eq!(comparison.p_values[0][1], comparison.p_values[1][0]);
+        assert!(comparison.a12[0][1] < 0.3);
+        assert_eq!(comparison.a12[0][1] + comparison.a12[1][0], 1.0);
+        assert_eq!(comparison.a12[2][0], 0.5);
+    }
+
+    #[test]
+    fn test_resume_from_checkpoint() {
+        let space_bounds = Domain::cube(2).bounds();
+        let path = std::env::temp_dir().join(format!("rart-resume-{}.jsonl", std::process::id()));
+        let _ = fs::remove_file(&path);
+        let mut config = EffectivenessConfig {
+            algorithms: vec![Algorithm::Rt, Algorithm::Fscs { cand_num: 10 }],
+            n_iter: 6,
+            n_repeat_fault_zone: 2,
+            seed: 5,
+            n_threads: Some(2),
+            ..Default::default()
+        };
+        let shape = Shape::Strip { rate: 0.9 };
+        let uninterrupted = run_trials(&config, 0.01, &space_bounds, &shape, 100, None).unwrap();
+
+        // Interrupted after 3 fault zones
+        config.n_iter = 3;
+        let checkpoint = Checkpoint::open(&path, config.seed).unwrap();
+        run_trials(&config, 0.01, &space_bounds, &shape, 100, Some(&checkpoint)).unwrap();
+        drop(checkpoint);
+
+        config.n_iter = 6;
+        let checkpoint = Checkpoint::open(&path, config.seed).unwrap();
+        assert_eq!(checkpoint.len(), 12);
+        let resumed =
+            run_trials(&config, 0.01, &space_bounds, &shape, 100, Some(&checkpoint)).unwrap();
+        drop(checkpoint);
+        assert_eq!(Checkpoint::open(&path, config.seed).unwrap().len(), 24);
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(resumed.len(), 12);
+        assert!(uninterrupted
+            .iter()
+            .zip(&resumed)
+            .all(|(u, r)| u.fault_zone == r.fault_zone
+                && u.repetition == r.repetition
+                && u.results == r.results));
+    }
+
+    #[test]
+    fn test_reject_area_sizes_without_failures() {
+        let output_dir = std::env::temp_dir().join(format!("rart-area-{}", std::process::id()));
+        let mut config = EffectivenessConfig {
+            area_sizes: vec![0.01, 0.0],
+            output_dir: output_dir.clone(),
+            ..Default::default()
+        };
+        let err = run_effectiveness(&config).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+        assert!(!output_dir.exists());
+
+        // A block of failure rate 0 is generated, but cannot be found
+        config.area_sizes = vec![0.0];
+        let mut rng = StdRng::seed_from_u64(1);
+        let space_bounds = Domain::cube(2).bounds();
+        assert!(matches!(
+            generate_fault_zone(&config, 0.0, &space_bounds, &Shape::Block, &mut rng),
+            Err(FaultZoneError::InvalidTheta { theta }) if theta == 0.0
+        ));
+    }
+}
@@ -0,0 +1,159 @@
+use std::fs;
+use std::path::PathBuf;
+use std::time::Instant;
+
+use serde::{Deserialize, Serialize};
+
+use crate::art::art_generator::ArtGenerator;
+use crate::art::kdfc_art::BackNumSchedule;
+use crate::util::seed::derive_seed;
+
+use super::{
+    check_algorithms,
+    raw::{csv_field, RawFormat, RawRecord, RawWriter},
+    save_config, Algorithm, Domain,
+};
+
+/// Number of untimed warm-up runs before the timed runs of each generator
+const N_WARM_UP: usize = 3;
+
+/// Parameters of an efficiency experiment, i.e. how long each algorithm takes to generate a
+/// number of test cases.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct EfficiencyConfig {
+    pub algorithms: Vec<Algorithm>,
+    pub domains: Vec<Domain>,
+    /// Numbers of test cases to generate
+    pub point_nums: Vec<usize>,
+    /// Number of timed runs per algorithm and number of test cases
+    pub n_runs: usize,
+    /// Master seed of all generators
+    pub seed: u64,
+    pub output_dir: PathBuf,
+    /// Format of the raw results file with one record per timed run
+    pub raw_format: RawFormat,
+}
+
+impl Default for EfficiencyConfig {
+    /// The configuration of the original efficiency study with a random seed.
+    fn default() -> Self {
+        Self {
+            algorithms: vec![
+                Algorithm::Lhs {
+                    n_partitions: Some(10),
+                    exhaustive: false,
+                },
+                Algorithm::Fscs { cand_num: 10 },
+                Algorithm::NaiveKdfc,
+                Algorithm::SemiBalKdfc,
+                Algorithm::LimBalKdfc {
+                    back_num: BackNumSchedule::default(),
+                },
+            ],
+            domains: vec![Domain::cube(2)],
+            point_nums: vec![100, 200, 500, 1000, 2000, 5000, 10000, 15000, 20000],
+            n_runs: 1000,
+            seed: rand::random(),
+            output_dir: PathBuf::from("test-results"),
+            raw_format: RawFormat::default(),
+        }
+    }
+}
+
+/// Times all configurations of an efficiency experiment. The run time of every timed run is written
+/// into a raw results file in the output directory, next to a copy of the configuration, and the
+/// mean run time of each algorithm, dimension count and number of test cases is printed.
+pub fn run_efficiency(config: &EfficiencyConfig) -> std::io::Result<()> {
+    check_algorithms(&config.algorithms, &config.domains)?;
+    let cur_time = chrono::Local::now().format("%Y-%m-%d_%H-%M-%S");
+    fs::create_dir_all(&config.output_dir)?;
+    save_config(config, &config.output_dir, &cur_time.to_string())?;
+    let mut raw_writer =
+        RawWriter::create(&config.output_dir, &cur_time.to_string(), config.raw_format)?;
+
+    println!("seed: {}", config.seed);
+
+    for domain in &config.domains {
+        let bd = domain.bounds();
+        let space_dims = bd.len();
+
+        for &n in &config.point_nums {
+            for (g, algorithm) in config.algorithms.iter().enumerate() {
+                let mut generator = algorithm.generator(&bd);
+                let seed = derive_seed(config.seed, g as u64);
+                generator.reseed(seed);
+                let record = EfficiencyRecord {
+                    algorithm: algorithm.label(),
+                    parameters: *algorithm,
+                    space_dim: space_dims,
+                    n_test_cases: n,
+                    seed,
+                    run: 0,
+                    wall_time_ms: 0.0,
+                };
+                test_generator(&mut raw_writer, record, generator.as_mut(), config.n_runs)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn test_generator(
+    raw: &mut RawWriter<EfficiencyRecord>,
+    mut record: EfficiencyRecord,
+    generator: &mut dyn ArtGenerator,
+    n_runs: usize,
+) -> std::io::Result<()> {
+    let mut sum = 0.0;
+    for i in 0..n_runs + N_WARM_UP {
+        let n1 = Instant::now();
+        generator.test_efficiency(record.n_test_cases);
+        let n2 = Instant::now();
+        if i >= N_WARM_UP {
+            sum += n2.duration_since(n1).as_nanos() as f64;
+            record.run = i - N_WARM_UP;
+            record.wall_time_ms = n2.duration_since(n1).as_nanos() as f64 / 1e6;
+            raw.write(&record)?;
+        }
+    }
+
+    let avg_time = sum / n_runs as f64 / 1e6;
+    println!("{} {:.6}\t", record.algorithm, avg_time);
+    raw.flush()?;
+    Ok(())
+}
+
+/// Raw result of one timed run of an algorithm
+#[derive(Debug, Clone, Serialize)]
+pub struct EfficiencyRecord {
+    pub algorithm: String,
+    pub parameters: Algorithm,
+    pub space_dim: usize,
+    pub n_test_cases: usize,
+    /// Seed of the generator before the warm-up runs
+    pub seed: u64,
+    pub run: usize,
+    pub wall_time_ms: f64,
+}
+
+impl RawRecord for EfficiencyRecord {
+    fn csv_header() -> &'static str {
+        "algorithm,parameters,space_dim,n_test_cases,seed,run,wall_time_ms"
+    }
+
+    fn csv_row(&self) -> String {
+        let parameters = serde_json::to_string(&self.parameters).unwrap_or_default();
+        format!(
+            "{},{},{},{},{},{},{}",
+            csv_field(&self.algorithm),
+            csv_field(&parameters),
+            self.space_dim,
+            self.n_test_cases,
+            self.seed,
+            self.run,
+            self.wall_time_ms,
+        )
+    }
+}
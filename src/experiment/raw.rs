@@ -0,0 +1,162 @@
+use std::borrow::Cow;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::marker::PhantomData;
+use std::path::Path;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+/// File format of the raw results of an experiment
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RawFormat {
+    /// One CSV row per record, below a header
+    #[default]
+    Csv,
+    /// One JSON object per line
+    Jsonl,
+}
+
+impl RawFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            RawFormat::Csv => "csv",
+            RawFormat::Jsonl => "jsonl",
+        }
+    }
+}
+
+impl FromStr for RawFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "csv" => Ok(RawFormat::Csv),
+            "jsonl" => Ok(RawFormat::Jsonl),
+            _ => Err(format!("unknown raw format '{s}', expected csv or jsonl")),
+        }
+    }
+}
+
+impl fmt::Display for RawFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.extension())
+    }
+}
+
+/// A record of the raw results of an experiment, e.g. a single trial
+pub trait RawRecord: Serialize {
+    /// Returns the CSV header, i.e. the names of the fields in the order of `csv_row`.
+    fn csv_header() -> &'static str;
+
+    /// Returns the fields of the record as a CSV row, without the line break.
+    fn csv_row(&self) -> String;
+}
+
+/// Writes raw records as CSV or JSON Lines.
+pub struct RawWriter<R, W: Write = BufWriter<File>> {
+    out: W,
+    format: RawFormat,
+    record: PhantomData<R>,
+}
+
+impl<R: RawRecord> RawWriter<R> {
+    /// Creates `raw-<time>.<extension>` in `dir` and writes the CSV header if needed.
+    pub fn create(dir: &Path, time: &str, format: RawFormat) -> io::Result<Self> {
+        let path = dir.join(format!("raw-{time}.{}", format.extension()));
+        Self::new(BufWriter::new(File::create(path)?), format)
+    }
+}
+
+impl<R: RawRecord, W: Write> RawWriter<R, W> {
+    pub fn new(mut out: W, format: RawFormat) -> io::Result<Self> {
+        if format == RawFormat::Csv {
+            writeln!(out, "{}", R::csv_header())?;
+        }
+        Ok(Self {
+            out,
+            format,
+            record: PhantomData,
+        })
+    }
+
+    pub fn write(&mut self, record: &R) -> io::Result<()> {
+        match self.format {
+            RawFormat::Csv => writeln!(self.out, "{}", record.csv_row()),
+            RawFormat::Jsonl => {
+                serde_json::to_writer(&mut self.out, record).map_err(io::Error::other)?;
+                writeln!(self.out)
+            }
+        }
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.out.flush()
+    }
+
+    pub fn into_inner(self) -> W {
+        self.out
+    }
+}
+
+/// Quotes a CSV field if it contains a separator, quote or line break.
+pub fn csv_field(field: &str) -> Cow<'_, str> {
+    if field.contains([',', '"', '\n', '\r']) {
+        Cow::Owned(format!("\"{}\"", field.replace('"', "\"\"")))
+    } else {
+        Cow::Borrowed(field)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[derive(Serialize)]
+    struct Record {
+        name: String,
+        value: f64,
+    }
+
+    impl RawRecord for Record {
+        fn csv_header() -> &'static str {
+            "name,value"
+        }
+
+        fn csv_row(&self) -> String {
+            format!("{},{}", csv_field(&self.name), self.value)
+        }
+    }
+
+    #[test]
+    fn test_raw_writer() {
+        let records = [
+            Record {
+                name: "plain".to_string(),
+                value: 1.5,
+            },
+            Record {
+                name: r#"{"a":1,"b":"c"}"#.to_string(),
+                value: 2.0,
+            },
+        ];
+
+        let mut csv = RawWriter::new(Vec::new(), RawFormat::Csv).unwrap();
+        let mut jsonl = RawWriter::new(Vec::new(), RawFormat::Jsonl).unwrap();
+        for record in &records {
+            csv.write(record).unwrap();
+            jsonl.write(record).unwrap();
+        }
+
+        assert_eq!(
+            String::from_utf8(csv.into_inner()).unwrap(),
+            "name,value\nplain,1.5\n\"{\"\"a\"\":1,\"\"b\"\":\"\"c\"\"}\",2\n"
+        );
+        assert_eq!(
+            String::from_utf8(jsonl.into_inner()).unwrap(),
+            "{\"name\":\"plain\",\"value\":1.5}\n{\"name\":\"{\\\"a\\\":1,\\\"b\\\":\\\"c\\\"}\",\"value\":2.0}\n"
+        );
+    }
+}
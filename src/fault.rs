@@ -2,3 +2,6 @@ pub mod fault_zone;
 pub mod fault_zone_strip;
 pub mod fault_zone_point_square;
 pub mod fault_zone_block;
+pub mod fault_zone_composite;
+pub mod fault_zone_ball;
+pub mod fault_zone_ellipsoid;
@@ -1,78 +1,272 @@
-use crate::util::point::Point;
+use std::fmt;
 
-use super::{
-    fault_zone_block::FaultZoneBlock, fault_zone_point_square::FaultZonePointSquare,
-    fault_zone_strip::FaultZoneStrip,
+use rand::Rng;
+
+use crate::util::{
+    geometry::domain_volume,
+    point::Point,
+    stats::{wilson_interval, Z_95},
 };
 
-macro_rules! fault_zone_enum {
-    ($($variant:ident($inner:tt)),*) => {
-        pub enum FaultZone<'this> {
-            $(
-                $variant($inner<'this>),
-            )*
-        }
+/// Number of random test cases used to estimate the failure rate of failure regions whose parts
+/// overlap
+pub const N_THETA_SAMPLES: usize = 100_000;
 
-        impl<'this> FaultZone<'this> {
-            pub fn find_target(&self, p: &Point) -> bool {
-                match self {
-                    $(
-                        FaultZone::$variant(inner) => inner.find_target(p),
-                    )*
-                }
-            }
+/// A failure region of a simulated program under test, i.e. the inputs for which the program
+/// fails. `ArtGenerator::test_effectiveness` counts how many test cases an algorithm needs to find
+/// one.
+///
+/// Implement this trait to evaluate the algorithms on custom failure regions.
+pub trait FaultZone {
+    /// Returns whether the test case `p` is inside the failure region.
+    fn find_target(&self, p: &Point) -> bool;
+
+    /// Returns the failure rate, i.e. the size of the failure region relative to the input domain.
+    fn get_theta(&self) -> f64;
+
+    /// Returns the half-width of a 95% confidence interval of `get_theta`, for failure regions
+    /// whose failure rate is estimated rather than exact.
+    fn theta_error(&self) -> f64 {
+        0.0
+    }
+
+    /// Returns the failure rate computed from the actual geometry of the failure region in
+    /// `input_domain`, or `None` if it cannot be computed exactly. Unlike `get_theta`, which may be
+    /// the nominal rate the region was generated for, this is what `measure_theta` checks against.
+    fn exact_theta(&self, _input_domain: &[Vec<i32>]) -> Option<f64> {
+        None
+    }
+
+    /// Returns the `[lower, upper]` bounds of each dimension of a box containing the failure
+    /// region, or `None` if the region has no known bounding box.
+    fn bounding_box(&self) -> Option<Vec<Vec<f64>>> {
+        None
+    }
+}
+
+/// Why a failure region could not be generated
+#[derive(Debug, Clone, PartialEq)]
+pub enum FaultZoneError {
+    /// The failure region, or one of its parts, does not fit into the input domain
+    DoesNotFit { theta: f64 },
+    /// No layout of non-overlapping parts was found within the attempt limit
+    NoLayout { n_regions: usize, attempts: usize },
+    /// The measured failure rate of the failure region deviates from the nominal one
+    ThetaMismatch {
+        nominal: f64,
+        measured: ThetaMeasurement,
+    },
+    /// The failure rate of the failure region is not positive and finite, e.g. for an empty
+    /// intersection, so no run could find it
+    InvalidTheta { theta: f64 },
+    /// A parameter of the failure region's layout is out of range
+    InvalidParameter { name: &'static str, value: f64 },
+}
 
-            pub fn get_theta(&self) -> f64 {
-                match self {
-                    $(
-                        FaultZone::$variant(inner) => inner.get_theta(),
-                    )*
-                }
+impl fmt::Display for FaultZoneError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FaultZoneError::DoesNotFit { theta } => write!(
+                f,
+                "a failure region covering {theta} of the input domain does not fit into it"
+            ),
+            FaultZoneError::NoLayout {
+                n_regions,
+                attempts,
+            } => write!(
+                f,
+                "no layout of {n_regions} non-overlapping failure regions found in {attempts} attempts"
+            ),
+            FaultZoneError::ThetaMismatch { nominal, measured } => write!(
+                f,
+                "failure region with nominal failure rate {nominal} measures {} (95% CI {}..{})",
+                measured.theta, measured.lower, measured.upper
+            ),
+            FaultZoneError::InvalidTheta { theta } => write!(
+                f,
+                "failure region with failure rate {theta} cannot be found, the rate must be positive and finite"
+            ),
+            FaultZoneError::InvalidParameter { name, value } => {
+                write!(f, "invalid failure region parameter {name} = {value}")
             }
         }
+    }
+}
+
+impl std::error::Error for FaultZoneError {}
+
+/// A measured failure rate with its 95% confidence interval
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ThetaMeasurement {
+    pub theta: f64,
+    pub lower: f64,
+    pub upper: f64,
+    /// Number of random test cases the measurement is based on, 0 if it is exact
+    pub n_samples: usize,
+}
+
+impl ThetaMeasurement {
+    fn exact(theta: f64) -> Self {
+        Self {
+            theta,
+            lower: theta,
+            upper: theta,
+            n_samples: 0,
+        }
+    }
+
+    pub fn is_exact(&self) -> bool {
+        self.n_samples == 0
+    }
+
+    /// Returns the largest distance between the measured failure rate and the bounds of its
+    /// confidence interval.
+    pub fn error(&self) -> f64 {
+        (self.upper - self.theta).max(self.theta - self.lower)
+    }
+
+    /// Returns whether the `nominal` failure rate is inside the confidence interval, widened by
+    /// the relative `tolerance`.
+    pub fn agrees_with(&self, nominal: f64, tolerance: f64) -> bool {
+        nominal >= self.lower * (1.0 - tolerance) && nominal <= self.upper * (1.0 + tolerance)
+    }
+}
+
+/// Measures the real failure rate of `fault_zone` in `input_domain`. The rate is computed exactly
+/// if the fault zone supports it, and otherwise estimated from `n_samples` random test cases.
+pub fn measure_theta<R: Rng + ?Sized>(
+    fault_zone: &dyn FaultZone,
+    input_domain: &[Vec<i32>],
+    n_samples: usize,
+    rng: &mut R,
+) -> ThetaMeasurement {
+    match fault_zone.exact_theta(input_domain) {
+        Some(theta) => ThetaMeasurement::exact(theta),
+        None => sample_theta(fault_zone, input_domain, n_samples, rng),
+    }
+}
+
+/// Estimates the failure rate of `fault_zone` from `n_samples` random test cases. The test cases
+/// are drawn inside the bounding box of the failure region when it has one, which makes the
+/// estimate of small regions more precise.
+///
+/// Panics if `n_samples` is 0, as no rate can be estimated from no test cases.
+pub fn sample_theta<R: Rng + ?Sized>(
+    fault_zone: &dyn FaultZone,
+    input_domain: &[Vec<i32>],
+    n_samples: usize,
+    rng: &mut R,
+) -> ThetaMeasurement {
+    assert!(
+        n_samples > 0,
+        "cannot estimate a failure rate from 0 samples"
+    );
+    let domain = input_domain
+        .iter()
+        .map(|b| vec![b[0] as f64, b[1] as f64])
+        .collect::<Vec<_>>();
+    let sample_box = match fault_zone.bounding_box() {
+        Some(bb) => clip_box(&bb, input_domain),
+        None => domain,
     };
+    if sample_box.iter().any(|b| b[0] >= b[1]) {
+        return ThetaMeasurement::exact(0.0);
+    }
+    let box_fraction =
+        sample_box.iter().map(|b| b[1] - b[0]).product::<f64>() / domain_volume(input_domain);
+
+    let hits = (0..n_samples)
+        .filter(|_| fault_zone.find_target(&Point::generate_rand_p_in(&sample_box, rng)))
+        .count();
+    let (lower, upper) = wilson_interval(hits, n_samples, Z_95);
+    ThetaMeasurement {
+        theta: box_fraction * hits as f64 / n_samples as f64,
+        lower: box_fraction * lower,
+        upper: box_fraction * upper,
+        n_samples,
+    }
+}
+
+/// Asserts that `fault_zone` covers `theta` of `input_domain`, up to five standard deviations of
+/// an estimate from 200 000 random test cases of the whole input domain.
+#[cfg(test)]
+pub(crate) fn assert_covers_theta<R: Rng + ?Sized>(
+    fault_zone: &dyn FaultZone,
+    input_domain: &[Vec<i32>],
+    theta: f64,
+    rng: &mut R,
+) {
+    let n_samples = 200_000;
+    let hits = (0..n_samples)
+        .filter(|_| fault_zone.find_target(&Point::generate_rand_p(input_domain, rng)))
+        .count();
+    let measured = hits as f64 / n_samples as f64;
+    let tolerance = 5.0 * (theta * (1.0 - theta) / n_samples as f64).sqrt();
+    assert!(
+        (measured - theta).abs() < tolerance,
+        "measured {measured}, expected {theta}"
+    );
+}
+
+/// Returns the part of the box `bb` inside the input domain. Boxes outside the input domain get a
+/// lower bound above the upper bound in some dimension.
+pub fn clip_box(bb: &[Vec<f64>], input_domain: &[Vec<i32>]) -> Vec<Vec<f64>> {
+    bb.iter()
+        .zip(input_domain)
+        .map(|(b, d)| vec![b[0].max(d[0] as f64), b[1].min(d[1] as f64)])
+        .collect()
 }
 
-fault_zone_enum!(
-    Block(FaultZoneBlock),
-    Strip(FaultZoneStrip),
-    PointSquare(FaultZonePointSquare)
-);
-
-// macro_rules! enum_with_impl {
-//     (
-//         pub enum $enum_name:ident 
-//             $($variant:ident($inner:tt)),* 
-//         impl 
-//             $($enum_method:ident($($enum_method_arg:ident: $enum_method_arg_type:ty),*) -> $enum_method_return:ty);*
-//     ) => {
-//         pub enum $enum_name<'this> {
-//             $(
-//                 $variant($inner<'this>),
-//             )*
-//         }
-
-//         impl<'this> $enum_name<'this> {
-//             $(
-//                 pub fn $enum_method(&self, $($enum_method_arg: $enum_method_arg_type),*) -> $enum_method_return {
-//                     match self {
-//                         $(
-//                             $enum_name::$variant(inner) => inner.$enum_method($($enum_method_arg),*),
-//                         )*
-//                     }
-//                 }
-//             )*
-//         }
-//     };
-// }
-
-// enum_with_impl!(
-//     pub enum FaultZone 
-//         Block(FaultZoneBlock),
-//         Strip(FaultZoneStrip),
-//         PointSquare(FaultZonePointSquare)
-//     impl 
-//         find_target(p: &Point) -> bool;
-//         get_theta() -> f64
-// );
+/// Returns the volume of the box `bb` relative to the input domain, or 0 if it is empty.
+pub fn box_theta(bb: &[Vec<f64>], input_domain: &[Vec<i32>]) -> f64 {
+    clip_box(bb, input_domain)
+        .iter()
+        .map(|b| (b[1] - b[0]).max(0.0))
+        .product::<f64>()
+        / domain_volume(input_domain)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::fault::{
+        fault_zone_ball::FaultZoneBall, fault_zone_block::FaultZoneBlock,
+        fault_zone_ellipsoid::FaultZoneEllipsoid, fault_zone_point_square::FaultZonePointSquare,
+        fault_zone_strip::FaultZoneStrip,
+    };
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn test_measured_theta_of_built_in_fault_zones() {
+        let mut rng = StdRng::seed_from_u64(29);
+        let input_domain = vec![vec![-5000, 5000], vec![0, 8000], vec![-100, 9900]];
+        let theta = 0.005;
+
+        let fault_zones: Vec<Box<dyn FaultZone>> = vec![
+            Box::new(FaultZoneBlock::new(&input_domain, theta, &mut rng).unwrap()),
+            Box::new(FaultZoneStrip::new(&input_domain, theta, 0.9, &mut rng).unwrap()),
+            Box::new(FaultZonePointSquare::new(&input_domain, theta, &mut rng).unwrap()),
+            Box::new(FaultZoneBall::new(&input_domain, theta, &mut rng).unwrap()),
+            Box::new(FaultZoneEllipsoid::new(&input_domain, theta, 3.0, true, &mut rng).unwrap()),
+        ];
 
+        for fault_zone in &fault_zones {
+            let exact = measure_theta(fault_zone.as_ref(), &input_domain, 100_000, &mut rng);
+            assert!(exact.is_exact());
+            assert!(exact.agrees_with(theta, 1e-4));
+
+            let sampled = sample_theta(fault_zone.as_ref(), &input_domain, 100_000, &mut rng);
+            assert!(!sampled.is_exact());
+            assert!(sampled.lower <= exact.theta && exact.theta <= sampled.upper);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "0 samples")]
+    fn test_sample_theta_without_samples() {
+        let mut rng = StdRng::seed_from_u64(31);
+        let input_domain = vec![vec![0, 100], vec![0, 100]];
+        let block = FaultZoneBlock::new(&input_domain, 0.1, &mut rng).unwrap();
+        sample_theta(&block, &input_domain, 0, &mut rng);
+    }
+}
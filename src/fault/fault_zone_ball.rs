@@ -0,0 +1,95 @@
+use rand::Rng;
+
+use crate::util::{
+    geometry::{domain_volume, unit_ball_volume},
+    point::Point,
+};
+
+use super::fault_zone::{clip_box, FaultZone, FaultZoneError};
+
+/// A ball covering `theta` of the input domain, placed randomly but entirely inside it.
+#[derive(Debug)]
+pub struct FaultZoneBall<'this> {
+    pub input_domain: &'this [Vec<i32>],
+    pub centre: Vec<f64>,
+    pub radius: f64,
+    pub theta: f64,
+}
+
+impl<'this> FaultZoneBall<'this> {
+    /// Fails if a ball of the requested volume does not fit into the input domain, which happens
+    /// in high dimensions already for small `area`.
+    pub fn new<R: Rng + ?Sized>(
+        boundary: &'this [Vec<i32>],
+        area: f64,
+        rng: &mut R,
+    ) -> Result<Self, FaultZoneError> {
+        let n = boundary.len();
+        let radius = (area * domain_volume(boundary) / unit_ball_volume(n)).powf(1.0 / n as f64);
+        if boundary.iter().any(|b| 2.0 * radius > (b[1] - b[0]) as f64) {
+            return Err(FaultZoneError::DoesNotFit { theta: area });
+        }
+
+        let centre = boundary
+            .iter()
+            .map(|b| {
+                let width = (b[1] - b[0]) as f64;
+                b[0] as f64 + radius + (width - 2.0 * radius) * rng.random::<f64>()
+            })
+            .collect();
+
+        Ok(Self {
+            input_domain: boundary,
+            centre,
+            radius,
+            theta: area,
+        })
+    }
+}
+
+impl FaultZone for FaultZoneBall<'_> {
+    fn find_target(&self, p: &Point) -> bool {
+        let dist_sq = (0..p.n)
+            .map(|i| (p.coordinates[i] as f64 - self.centre[i]).powi(2))
+            .sum::<f64>();
+        dist_sq <= self.radius * self.radius
+    }
+
+    fn get_theta(&self) -> f64 {
+        self.theta
+    }
+
+    /// Exact if the ball lies entirely inside the input domain.
+    fn exact_theta(&self, input_domain: &[Vec<i32>]) -> Option<f64> {
+        let bb = self.bounding_box()?;
+        (clip_box(&bb, input_domain) == bb).then(|| {
+            unit_ball_volume(self.centre.len()) * self.radius.powi(self.centre.len() as i32)
+                / domain_volume(input_domain)
+        })
+    }
+
+    fn bounding_box(&self) -> Option<Vec<Vec<f64>>> {
+        Some(
+            self.centre
+                .iter()
+                .map(|c| vec![c - self.radius, c + self.radius])
+                .collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::fault::fault_zone::assert_covers_theta;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn test_ball_covers_theta_of_input_domain() {
+        let mut rng = StdRng::seed_from_u64(13);
+        let input_domain = vec![vec![-5000, 5000], vec![0, 8000], vec![-100, 9900]];
+        let theta = 0.05;
+        let ball = FaultZoneBall::new(&input_domain, theta, &mut rng).unwrap();
+        assert_covers_theta(&ball, &input_domain, theta, &mut rng);
+    }
+}
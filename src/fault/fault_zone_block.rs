@@ -1,49 +1,119 @@
 use crate::util::point::Point;
 use rand::Rng;
 
+use super::fault_zone::{box_theta, FaultZone, FaultZoneError};
+
 #[derive(Debug)]
 pub struct FaultZoneBlock<'a> {
     pub input_domain: &'a [Vec<i32>],
-    pub fault_point: Point,
+    /// Lower corner of the hypercube
+    pub lower: Vec<f64>,
     pub delta: f64,
     pub theta: f64,
 }
 
 impl<'a> FaultZoneBlock<'a> {
-    pub fn new(boundary: &'a [Vec<i32>], area: f64) -> Self {
+    /// Fails if a hypercube of the requested volume is wider than a dimension of the input domain.
+    pub fn new<R: Rng + ?Sized>(
+        boundary: &'a [Vec<i32>],
+        area: f64,
+        rng: &mut R,
+    ) -> Result<Self, FaultZoneError> {
         let n = boundary.len();
         let mut sum = 1.0;
         (0..n).for_each(|i| {
             sum *= (boundary[i][1] - boundary[i][0]) as f64;
         });
         let delta = (sum * area).powf(1.0 / n as f64);
+        if boundary.iter().any(|b| delta > (b[1] - b[0]) as f64) {
+            return Err(FaultZoneError::DoesNotFit { theta: area });
+        }
 
-        let mut fault_point = Point::new(n);
-        (0..n).for_each(|i| {
-            fault_point.coordinates[i] = boundary[i][0] as f32
-                + ((boundary[i][1] - boundary[i][0]) as f32 - delta as f32) * rand::rng().random::<f32>();
-        });
+        let lower = boundary
+            .iter()
+            .map(|b| b[0] as f64 + ((b[1] - b[0]) as f64 - delta) * rng.random::<f64>())
+            .collect();
 
-        Self {
+        Ok(Self {
             input_domain: boundary,
-            fault_point,
+            lower,
             delta,
             theta: area,
-        }
+        })
     }
+}
 
-    pub fn find_target(&self, p: &Point) -> bool {
-        for i in 0..p.n {
-            if !((p.coordinates[i] >= self.fault_point.coordinates[i])
-                && (p.coordinates[i] <= self.fault_point.coordinates[i] + self.delta as f32))
-            {
-                return false;
-            }
-        }
-        true
+impl FaultZone for FaultZoneBlock<'_> {
+    fn find_target(&self, p: &Point) -> bool {
+        // In f64 like `bounding_box`, so that both describe the same box
+        (0..p.n).all(|i| {
+            let c = p.coordinates[i] as f64;
+            c >= self.lower[i] && c <= self.lower[i] + self.delta
+        })
     }
 
-    pub fn get_theta(&self) -> f64 {
+    fn get_theta(&self) -> f64 {
         self.theta
     }
+
+    fn exact_theta(&self, input_domain: &[Vec<i32>]) -> Option<f64> {
+        Some(box_theta(&self.bounding_box()?, input_domain))
+    }
+
+    fn bounding_box(&self) -> Option<Vec<Vec<f64>>> {
+        Some(
+            self.lower
+                .iter()
+                .map(|&c| vec![c, c + self.delta])
+                .collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn test_block_fits_into_input_domain() {
+        let mut rng = StdRng::seed_from_u64(17);
+        let input_domain = vec![vec![0, 10000], vec![0, 10]];
+
+        assert_eq!(
+            FaultZoneBlock::new(&input_domain, 0.05, &mut rng).unwrap_err(),
+            FaultZoneError::DoesNotFit { theta: 0.05 }
+        );
+
+        // Nearly as wide as a small input domain far from 0, where f32 steps are 0.25 apart and
+        // rounding the placement would push the block out of the input domain
+        let far = vec![vec![4_000_000, 4_000_010], vec![4_000_000, 4_000_010]];
+        for _ in 0..1000 {
+            let block = FaultZoneBlock::new(&far, 0.97, &mut rng).unwrap();
+            let bb = block.bounding_box().unwrap();
+            assert!(bb
+                .iter()
+                .zip(&far)
+                .all(|(b, d)| b[0] >= d[0] as f64 && b[1] <= d[1] as f64));
+            assert!((block.exact_theta(&far).unwrap() - 0.97).abs() < 1e-9);
+        }
+
+        let block = FaultZoneBlock::new(&input_domain, 0.0005, &mut rng).unwrap();
+        let bb = block.bounding_box().unwrap();
+        assert!((0..2).all(|d| bb[d][0] >= 0.0 && bb[d][1] <= input_domain[d][1] as f64));
+
+        // `find_target` hits exactly the bounding box, also at its edges
+        let around = bb
+            .iter()
+            .map(|b| vec![b[0] - 1.0, b[1] + 1.0])
+            .collect::<Vec<_>>();
+        for _ in 0..10_000 {
+            let p = Point::generate_rand_p_in(&around, &mut rng);
+            let in_box = (0..2).all(|d| {
+                let c = p.coordinates[d] as f64;
+                bb[d][0] <= c && c <= bb[d][1]
+            });
+            assert_eq!(block.find_target(&p), in_box);
+        }
+    }
 }
@@ -0,0 +1,284 @@
+use rand::Rng;
+
+use crate::util::point::Point;
+
+use super::fault_zone::{sample_theta, FaultZone, N_THETA_SAMPLES};
+
+/// How the parts of a `FaultZoneComposite` are combined
+pub enum SetOperation<'this> {
+    /// Inputs in any of the parts
+    Union(Vec<Box<dyn FaultZone + 'this>>),
+    /// Inputs in all of the parts
+    Intersection(Vec<Box<dyn FaultZone + 'this>>),
+    /// Inputs in the first part but not in the second
+    Difference(Box<dyn FaultZone + 'this>, Box<dyn FaultZone + 'this>),
+    /// Inputs outside the part
+    Complement(Box<dyn FaultZone + 'this>),
+}
+
+/// A failure region combined from other failure regions by set algebra, e.g. two blocks and a
+/// strip, or a block without a ball.
+///
+/// The failure rate is exact when the parts cannot overlap, judged by their bounding boxes.
+/// Otherwise it is estimated from `N_THETA_SAMPLES` random inputs, drawn inside the bounding box
+/// of the composite when there is one, and `theta_error` bounds its error.
+pub struct FaultZoneComposite<'this> {
+    pub input_domain: &'this [Vec<i32>],
+    pub operation: SetOperation<'this>,
+    pub theta: f64,
+    /// Half-width of the 95% confidence interval of `theta`, 0 if it is exact
+    pub theta_error: f64,
+}
+
+impl<'this> FaultZoneComposite<'this> {
+    pub fn union<R: Rng + ?Sized>(
+        input_domain: &'this [Vec<i32>],
+        parts: Vec<Box<dyn FaultZone + 'this>>,
+        rng: &mut R,
+    ) -> Self {
+        Self::new(input_domain, SetOperation::Union(parts), rng)
+    }
+
+    pub fn intersection<R: Rng + ?Sized>(
+        input_domain: &'this [Vec<i32>],
+        parts: Vec<Box<dyn FaultZone + 'this>>,
+        rng: &mut R,
+    ) -> Self {
+        Self::new(input_domain, SetOperation::Intersection(parts), rng)
+    }
+
+    pub fn difference<R: Rng + ?Sized>(
+        input_domain: &'this [Vec<i32>],
+        part: Box<dyn FaultZone + 'this>,
+        removed: Box<dyn FaultZone + 'this>,
+        rng: &mut R,
+    ) -> Self {
+        Self::new(input_domain, SetOperation::Difference(part, removed), rng)
+    }
+
+    pub fn complement(input_domain: &'this [Vec<i32>], part: Box<dyn FaultZone + 'this>) -> Self {
+        let (theta, theta_error) = (1.0 - part.get_theta(), part.theta_error());
+        Self {
+            input_domain,
+            operation: SetOperation::Complement(part),
+            theta,
+            theta_error,
+        }
+    }
+
+    fn new<R: Rng + ?Sized>(
+        input_domain: &'this [Vec<i32>],
+        operation: SetOperation<'this>,
+        rng: &mut R,
+    ) -> Self {
+        let mut fzc = Self {
+            input_domain,
+            operation,
+            theta: 0.0,
+            theta_error: 0.0,
+        };
+        (fzc.theta, fzc.theta_error) = match fzc.combined_theta() {
+            Some(exact) => exact,
+            None => {
+                let measured = sample_theta(&fzc, input_domain, N_THETA_SAMPLES, rng);
+                (measured.theta, measured.error())
+            }
+        };
+        fzc
+    }
+
+    /// Returns the failure rate and its error combined from those of the parts, when the parts
+    /// cannot overlap.
+    fn combined_theta(&self) -> Option<(f64, f64)> {
+        match &self.operation {
+            SetOperation::Union(parts) => {
+                let boxes = parts
+                    .iter()
+                    .map(|part| part.bounding_box())
+                    .collect::<Option<Vec<_>>>()?;
+                pairwise_disjoint(&boxes).then(|| {
+                    (
+                        parts.iter().map(|part| part.get_theta()).sum(),
+                        parts.iter().map(|part| part.theta_error()).sum(),
+                    )
+                })
+            }
+            SetOperation::Intersection(_) => self
+                .bounding_box()
+                .is_some_and(|bb| bb.iter().any(|b| b[0] > b[1]))
+                .then_some((0.0, 0.0)),
+            SetOperation::Difference(part, removed) => {
+                match (part.bounding_box(), removed.bounding_box()) {
+                    (Some(a), Some(b)) if !boxes_overlap(&a, &b) => {
+                        Some((part.get_theta(), part.theta_error()))
+                    }
+                    _ => None,
+                }
+            }
+            SetOperation::Complement(part) => Some((1.0 - part.get_theta(), part.theta_error())),
+        }
+    }
+}
+
+impl FaultZone for FaultZoneComposite<'_> {
+    fn find_target(&self, p: &Point) -> bool {
+        match &self.operation {
+            SetOperation::Union(parts) => parts.iter().any(|part| part.find_target(p)),
+            SetOperation::Intersection(parts) => parts.iter().all(|part| part.find_target(p)),
+            SetOperation::Difference(part, removed) => {
+                part.find_target(p) && !removed.find_target(p)
+            }
+            SetOperation::Complement(part) => !part.find_target(p),
+        }
+    }
+
+    fn get_theta(&self) -> f64 {
+        self.theta
+    }
+
+    fn theta_error(&self) -> f64 {
+        self.theta_error
+    }
+
+    /// Exact if the exact failure rates of the parts can be combined, i.e. for complements and
+    /// for parts that cannot overlap.
+    fn exact_theta(&self, input_domain: &[Vec<i32>]) -> Option<f64> {
+        match &self.operation {
+            SetOperation::Union(parts) => {
+                let boxes = parts
+                    .iter()
+                    .map(|part| part.bounding_box())
+                    .collect::<Option<Vec<_>>>()?;
+                if !pairwise_disjoint(&boxes) {
+                    return None;
+                }
+                parts
+                    .iter()
+                    .map(|part| part.exact_theta(input_domain))
+                    .sum()
+            }
+            SetOperation::Intersection(_) => self
+                .bounding_box()
+                .is_some_and(|bb| bb.iter().any(|b| b[0] > b[1]))
+                .then_some(0.0),
+            SetOperation::Difference(part, removed) => {
+                match (part.bounding_box(), removed.bounding_box()) {
+                    (Some(a), Some(b)) if !boxes_overlap(&a, &b) => part.exact_theta(input_domain),
+                    _ => None,
+                }
+            }
+            SetOperation::Complement(part) => part.exact_theta(input_domain).map(|t| 1.0 - t),
+        }
+    }
+
+    fn bounding_box(&self) -> Option<Vec<Vec<f64>>> {
+        match &self.operation {
+            SetOperation::Union(parts) => {
+                let boxes = parts
+                    .iter()
+                    .map(|part| part.bounding_box())
+                    .collect::<Option<Vec<_>>>()?;
+                boxes.into_iter().reduce(|hull, b| {
+                    hull.iter()
+                        .zip(&b)
+                        .map(|(h, b)| vec![h[0].min(b[0]), h[1].max(b[1])])
+                        .collect()
+                })
+            }
+            SetOperation::Intersection(parts) => parts
+                .iter()
+                .filter_map(|part| part.bounding_box())
+                .reduce(|common, b| {
+                    common
+                        .iter()
+                        .zip(&b)
+                        .map(|(c, b)| vec![c[0].max(b[0]), c[1].min(b[1])])
+                        .collect()
+                }),
+            SetOperation::Difference(part, _) => part.bounding_box(),
+            SetOperation::Complement(_) => None,
+        }
+    }
+}
+
+fn pairwise_disjoint(boxes: &[Vec<Vec<f64>>]) -> bool {
+    (0..boxes.len()).all(|i| (i + 1..boxes.len()).all(|j| !boxes_overlap(&boxes[i], &boxes[j])))
+}
+
+fn boxes_overlap(a: &[Vec<f64>], b: &[Vec<f64>]) -> bool {
+    a.iter().zip(b).all(|(a, b)| a[0] <= b[1] && b[0] <= a[1])
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::fault::fault_zone_block::FaultZoneBlock;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    fn block(input_domain: &[Vec<i32>], corner: [f64; 2], delta: f64) -> Box<FaultZoneBlock<'_>> {
+        Box::new(FaultZoneBlock {
+            input_domain,
+            lower: corner.to_vec(),
+            delta,
+            theta: delta * delta / 10000.0,
+        })
+    }
+
+    #[test]
+    fn test_composite_theta() {
+        let mut rng = StdRng::seed_from_u64(19);
+        let input_domain = vec![vec![0, 100], vec![0, 100]];
+
+        // Overlapping blocks of 50 x 50 sharing a 25 x 25 square
+        let cases = [
+            (
+                FaultZoneComposite::union(
+                    &input_domain,
+                    vec![
+                        block(&input_domain, [0.0, 0.0], 50.0),
+                        block(&input_domain, [25.0, 25.0], 50.0),
+                    ],
+                    &mut rng,
+                ),
+                0.4375,
+            ),
+            (
+                FaultZoneComposite::intersection(
+                    &input_domain,
+                    vec![
+                        block(&input_domain, [0.0, 0.0], 50.0),
+                        block(&input_domain, [25.0, 25.0], 50.0),
+                    ],
+                    &mut rng,
+                ),
+                0.0625,
+            ),
+            (
+                FaultZoneComposite::difference(
+                    &input_domain,
+                    block(&input_domain, [0.0, 0.0], 50.0),
+                    block(&input_domain, [25.0, 25.0], 50.0),
+                    &mut rng,
+                ),
+                0.1875,
+            ),
+        ];
+        for (composite, expected) in cases {
+            assert!(composite.theta_error > 0.0 && composite.theta_error < 0.005);
+            assert!((composite.theta - expected).abs() <= composite.theta_error);
+        }
+
+        // Disjoint parts and complements are exact
+        let union = FaultZoneComposite::union(
+            &input_domain,
+            vec![
+                block(&input_domain, [0.0, 0.0], 20.0),
+                block(&input_domain, [50.0, 50.0], 30.0),
+            ],
+            &mut rng,
+        );
+        assert_eq!((union.theta, union.theta_error), (0.13, 0.0));
+        let complement = FaultZoneComposite::complement(&input_domain, Box::new(union));
+        assert_eq!((complement.theta, complement.theta_error), (0.87, 0.0));
+    }
+}
@@ -0,0 +1,149 @@
+use rand::Rng;
+
+use crate::util::{
+    geometry::{domain_volume, random_rotation, unit_ball_volume},
+    point::Point,
+};
+
+use super::fault_zone::{clip_box, FaultZone, FaultZoneError};
+
+/// An ellipsoid covering `theta` of the input domain, placed randomly but entirely inside it.
+///
+/// The ellipsoid is elongated along its first axis, which is `aspect_ratio` times as long as the
+/// other axes. Without rotation the axes are parallel to the coordinate axes.
+#[derive(Debug)]
+pub struct FaultZoneEllipsoid<'this> {
+    pub input_domain: &'this [Vec<i32>],
+    pub centre: Vec<f64>,
+    /// Unit vectors along the axes of the ellipsoid
+    pub axes: Vec<Vec<f64>>,
+    pub semi_axes: Vec<f64>,
+    pub theta: f64,
+}
+
+impl<'this> FaultZoneEllipsoid<'this> {
+    /// Fails if an ellipsoid of the requested volume does not fit into the input domain.
+    pub fn new<R: Rng + ?Sized>(
+        boundary: &'this [Vec<i32>],
+        area: f64,
+        aspect_ratio: f64,
+        rotate: bool,
+        rng: &mut R,
+    ) -> Result<Self, FaultZoneError> {
+        let n = boundary.len();
+        // The semi-axes multiply to radius^n, the volume of a ball of the same size
+        let radius = (area * domain_volume(boundary) / unit_ball_volume(n)).powf(1.0 / n as f64);
+        let short = radius * aspect_ratio.powf(-1.0 / n as f64);
+        let semi_axes = (0..n)
+            .map(|i| if i == 0 { short * aspect_ratio } else { short })
+            .collect::<Vec<_>>();
+
+        let axes = if rotate {
+            random_rotation(n, rng)
+        } else {
+            (0..n)
+                .map(|i| (0..n).map(|j| if i == j { 1.0 } else { 0.0 }).collect())
+                .collect()
+        };
+
+        let half_extents = (0..n)
+            .map(|k| half_extent(&axes, &semi_axes, k))
+            .collect::<Vec<_>>();
+        if (0..n).any(|k| 2.0 * half_extents[k] > (boundary[k][1] - boundary[k][0]) as f64) {
+            return Err(FaultZoneError::DoesNotFit { theta: area });
+        }
+
+        let centre = (0..n)
+            .map(|k| {
+                let width = (boundary[k][1] - boundary[k][0]) as f64;
+                boundary[k][0] as f64
+                    + half_extents[k]
+                    + (width - 2.0 * half_extents[k]) * rng.random::<f64>()
+            })
+            .collect();
+
+        Ok(Self {
+            input_domain: boundary,
+            centre,
+            axes,
+            semi_axes,
+            theta: area,
+        })
+    }
+}
+
+impl FaultZone for FaultZoneEllipsoid<'_> {
+    fn find_target(&self, p: &Point) -> bool {
+        let offset = (0..p.n)
+            .map(|k| p.coordinates[k] as f64 - self.centre[k])
+            .collect::<Vec<_>>();
+        let dist_sq = self
+            .axes
+            .iter()
+            .zip(&self.semi_axes)
+            .map(|(axis, semi_axis)| {
+                let projection = axis.iter().zip(&offset).map(|(a, o)| a * o).sum::<f64>();
+                (projection / semi_axis).powi(2)
+            })
+            .sum::<f64>();
+        dist_sq <= 1.0
+    }
+
+    fn get_theta(&self) -> f64 {
+        self.theta
+    }
+
+    /// Exact if the ellipsoid lies entirely inside the input domain.
+    fn exact_theta(&self, input_domain: &[Vec<i32>]) -> Option<f64> {
+        let bb = self.bounding_box()?;
+        (clip_box(&bb, input_domain) == bb).then(|| {
+            unit_ball_volume(self.centre.len()) * self.semi_axes.iter().product::<f64>()
+                / domain_volume(input_domain)
+        })
+    }
+
+    fn bounding_box(&self) -> Option<Vec<Vec<f64>>> {
+        Some(
+            (0..self.centre.len())
+                .map(|k| {
+                    let half_extent = half_extent(&self.axes, &self.semi_axes, k);
+                    vec![self.centre[k] - half_extent, self.centre[k] + half_extent]
+                })
+                .collect(),
+        )
+    }
+}
+
+/// Returns half of the extent of an ellipsoid along coordinate axis `k`.
+fn half_extent(axes: &[Vec<f64>], semi_axes: &[f64], k: usize) -> f64 {
+    axes.iter()
+        .zip(semi_axes)
+        .map(|(axis, semi_axis)| (axis[k] * semi_axis).powi(2))
+        .sum::<f64>()
+        .sqrt()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::fault::fault_zone::assert_covers_theta;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn test_ellipsoid_covers_theta_of_input_domain() {
+        let mut rng = StdRng::seed_from_u64(17);
+        let input_domain = vec![
+            vec![-5000, 5000],
+            vec![0, 8000],
+            vec![-100, 9900],
+            vec![0, 10000],
+        ];
+        let theta = 0.005;
+
+        for rotate in [false, true] {
+            let ellipsoid =
+                FaultZoneEllipsoid::new(&input_domain, theta, 3.0, rotate, &mut rng).unwrap();
+            assert_covers_theta(&ellipsoid, &input_domain, theta, &mut rng);
+        }
+    }
+}
@@ -1,116 +1,398 @@
-use rand::rngs::StdRng;
-use rand::{Rng, SeedableRng};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
 
-use crate::util::point::Point;
+use crate::util::{geometry::domain_volume, point::Point};
 
+use super::fault_zone::{box_theta, sample_theta, FaultZone, FaultZoneError, N_THETA_SAMPLES};
+
+/// Total number of region placements before the layout of non-overlapping regions is given up
+const MAX_PLACEMENT_ATTEMPTS: usize = 1_000_000;
+
+/// Number of placements of a single region before the layout is started over
+const MAX_REGION_ATTEMPTS: usize = 10_000;
+
+/// How the failure rate is divided between the regions of a point pattern
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(tag = "distribution", rename_all = "kebab-case")]
+pub enum SizeDistribution {
+    /// All regions have the same size
+    #[default]
+    Equal,
+    /// The failure rate is split at uniformly random points
+    RandomSplit,
+    /// The `i`th largest region is proportional to `i^-exponent`
+    PowerLaw { exponent: f64 },
+}
+
+/// Whether the regions of a point pattern may overlap
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum OverlapPolicy {
+    /// Regions are redrawn until they do not overlap, so that the failure rate is exact
+    #[default]
+    Disjoint,
+    /// Regions are placed independently. The failure rate of their union is estimated, and is
+    /// lower than requested where regions overlap.
+    Allow,
+}
+
+/// Layout of a point pattern fault zone
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PointPattern {
+    pub n_regions: usize,
+    pub sizes: SizeDistribution,
+    /// If set, the regions are placed inside a random box of this fraction, in (0, 1], of the input
+    /// domain in each dimension
+    pub cluster_spread: Option<f64>,
+    pub overlap: OverlapPolicy,
+}
+
+impl Default for PointPattern {
+    /// 25 equal, non-overlapping squares anywhere in the input domain.
+    fn default() -> Self {
+        Self {
+            n_regions: 25,
+            sizes: SizeDistribution::default(),
+            cluster_spread: None,
+            overlap: OverlapPolicy::default(),
+        }
+    }
+}
+
+impl PointPattern {
+    /// Returns an error if the pattern has no regions, a cluster spread outside (0, 1] or a power
+    /// law exponent that is not finite.
+    pub fn validate(&self) -> Result<(), FaultZoneError> {
+        if self.n_regions == 0 {
+            return Err(FaultZoneError::InvalidParameter {
+                name: "n_regions",
+                value: 0.0,
+            });
+        }
+        if let Some(spread) = self.cluster_spread {
+            if !(spread > 0.0 && spread <= 1.0) {
+                return Err(FaultZoneError::InvalidParameter {
+                    name: "cluster_spread",
+                    value: spread,
+                });
+            }
+        }
+        if let SizeDistribution::PowerLaw { exponent } = self.sizes {
+            if !exponent.is_finite() {
+                return Err(FaultZoneError::InvalidParameter {
+                    name: "exponent",
+                    value: exponent,
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A point pattern, i.e. a number of small hypercubes that together cover `theta` of the input
+/// domain.
 #[derive(Debug)]
 pub struct FaultZonePointSquare<'this> {
     pub input_domain: &'this [Vec<i32>],
     pub n_points: usize,
+    /// Lower corners of the hypercubes
     pub fault_points: Vec<Point>,
-    pub delta: f64,
+    /// Edge lengths of the hypercubes
+    pub deltas: Vec<f64>,
     pub theta: f64,
+    /// Half-width of the 95% confidence interval of `theta`, 0 unless the regions may overlap
+    pub theta_error: f64,
 }
 
 impl<'this> FaultZonePointSquare<'this> {
-    pub fn new(input_domain: &'this [Vec<i32>], theta: f64) -> Self {
-        let n_dims = input_domain.len();
-        let n_points = 25;
-
-        // println!("theta: {theta}");
+    pub fn new<R: Rng + ?Sized>(
+        input_domain: &'this [Vec<i32>],
+        theta: f64,
+        rng: &mut R,
+    ) -> Result<Self, FaultZoneError> {
+        Self::with_pattern(input_domain, theta, &PointPattern::default(), rng)
+    }
 
-        let mut sum = 1.0f64;
-        (0..n_dims).for_each(|i| {
-            sum *= (input_domain[i][1] - input_domain[i][0]) as f64;
-        });
-        let delta = (sum * theta / n_points as f64).powf(1.0 / n_dims as f64);
+    pub fn with_pattern<R: Rng + ?Sized>(
+        input_domain: &'this [Vec<i32>],
+        theta: f64,
+        pattern: &PointPattern,
+        rng: &mut R,
+    ) -> Result<Self, FaultZoneError> {
+        pattern.validate()?;
+        let n_dims = input_domain.len();
+        let n_points = pattern.n_regions;
 
-        let mut fault_points = vec![];
+        // Largest regions first, as they are the hardest to place
+        let mut thetas = region_thetas(theta, n_points, &pattern.sizes, rng);
+        thetas.sort_by(|a, b| b.total_cmp(a));
+        let volume = domain_volume(input_domain);
+        let deltas = thetas
+            .iter()
+            .map(|t| (volume * t).powf(1.0 / n_dims as f64))
+            .collect::<Vec<_>>();
 
-        let mut n_overlaps = 0;
-        let mut rng = StdRng::from_os_rng();
+        let area = match pattern.cluster_spread {
+            Some(spread) => (0..n_dims)
+                .map(|i| {
+                    let (lower, upper) = (input_domain[i][0] as f64, input_domain[i][1] as f64);
+                    let width = (upper - lower) * spread;
+                    let start = lower + (upper - lower - width) * rng.random::<f64>();
+                    vec![start, start + width]
+                })
+                .collect::<Vec<_>>(),
+            None => input_domain
+                .iter()
+                .map(|b| vec![b[0] as f64, b[1] as f64])
+                .collect(),
+        };
+        if deltas
+            .iter()
+            .any(|&delta| area.iter().any(|b| b[1] - b[0] < delta))
+        {
+            return Err(FaultZoneError::DoesNotFit { theta });
+        }
 
+        let mut fault_points: Vec<Point> = Vec::with_capacity(n_points);
+        let mut n_attempts = 0;
+        let mut n_region_attempts = 0;
         while fault_points.len() < n_points {
-            let mut fault_point_candidate = Point::new(n_dims);
-            loop {
-                (0..n_dims).for_each(|i| {
-                    let coordinate = input_domain[i][0] as f32
-                        + (((input_domain[i][1] - input_domain[i][0]) as f64 - delta)
-                            * rng.random::<f64>()) as f32;
-                    fault_point_candidate.coordinates[i] = coordinate;
-                });
+            let delta = deltas[fault_points.len()];
+            let mut candidate = Point::new(n_dims);
+            (0..n_dims).for_each(|i| {
+                candidate.coordinates[i] =
+                    (area[i][0] + (area[i][1] - area[i][0] - delta) * rng.random::<f64>()) as f32;
+            });
 
-                if !Self::is_overlap(&fault_point_candidate, delta, &fault_points) {
-                    break;
-                }
-                n_overlaps += 1;
-                if n_overlaps > 1e6 as usize {
-                    fault_points.clear();
-                    n_overlaps = 0;
-                }
+            let overlaps = pattern.overlap == OverlapPolicy::Disjoint
+                && fault_points
+                    .iter()
+                    .zip(&deltas)
+                    .any(|(p, &d)| Self::is_overlap(&candidate, delta, p, d));
+            if !overlaps {
+                fault_points.push(candidate);
+                n_region_attempts = 0;
+                continue;
             }
-            fault_points.push(fault_point_candidate);
-
-            // Print maximum and minimum values for each dimension across all candidates
-            // let mut max = vec![0.0; n_dims];
-            // let mut min = vec![0.0; n_dims];
-            // for i in 0..n_dims {
-            //     max[i] = fault_points.iter().map(|p| p.coordinates[i]).fold(f32::MIN, f32::max);
-            //     min[i] = fault_points.iter().map(|p| p.coordinates[i]).fold(f32::MAX, f32::min);
-            // }
-            // println!("max: {:?}", max);
-            // println!("min: {:?}", min);
 
+            n_attempts += 1;
+            n_region_attempts += 1;
+            if n_attempts >= MAX_PLACEMENT_ATTEMPTS {
+                return Err(FaultZoneError::NoLayout {
+                    n_regions: n_points,
+                    attempts: n_attempts,
+                });
+            }
+            if n_region_attempts >= MAX_REGION_ATTEMPTS {
+                fault_points.clear();
+                n_region_attempts = 0;
+            }
         }
 
-        Self {
+        let mut fzps = Self {
             input_domain,
             n_points,
             fault_points,
-            delta,
+            deltas,
             theta,
+            theta_error: 0.0,
+        };
+        if pattern.overlap == OverlapPolicy::Allow {
+            let measured = sample_theta(&fzps, input_domain, N_THETA_SAMPLES, rng);
+            (fzps.theta, fzps.theta_error) = (measured.theta, measured.error());
         }
+        Ok(fzps)
     }
 
-    fn is_overlap(p: &Point, delta: f64, fault_points: &[Point]) -> bool {
-        if fault_points.is_empty() {
-            return false;
-        }
-
-        for fault_point in fault_points {
-            let mut overlap = true;
-            for dim in 0..p.n {
-                if (p.coordinates[dim] - fault_point.coordinates[dim]).abs() as f64 > delta {
-                    overlap = false;
-                    break;
-                }
-            }
-            if overlap {
-                return true;
-            }
-        }
-        false
+    fn is_overlap(p1: &Point, delta1: f64, p2: &Point, delta2: f64) -> bool {
+        (0..p1.n).all(|dim| {
+            let (c1, c2) = (p1.coordinates[dim] as f64, p2.coordinates[dim] as f64);
+            c1 <= c2 + delta2 && c2 <= c1 + delta1
+        })
     }
+}
 
-    pub fn find_target(&self, p: &Point) -> bool {
-        for fault_point in &self.fault_points {
-            let mut found = true;
-            for j in 0..p.n {
-                if !(p.coordinates[j] >= fault_point.coordinates[j]
-                    && p.coordinates[j] as f64 <= fault_point.coordinates[j] as f64 + self.delta)
-                {
-                    found = false;
-                    break;
-                }
-            }
-            if found {
-                return true;
-            }
-        }
-        false
+impl FaultZone for FaultZonePointSquare<'_> {
+    fn find_target(&self, p: &Point) -> bool {
+        self.fault_points
+            .iter()
+            .zip(&self.deltas)
+            .any(|(fault_point, &delta)| {
+                (0..p.n).all(|j| {
+                    p.coordinates[j] >= fault_point.coordinates[j]
+                        && p.coordinates[j] as f64 <= fault_point.coordinates[j] as f64 + delta
+                })
+            })
     }
 
-    pub fn get_theta(&self) -> f64 {
+    fn get_theta(&self) -> f64 {
         self.theta
     }
+
+    fn theta_error(&self) -> f64 {
+        self.theta_error
+    }
+
+    /// Exact if no two hypercubes overlap.
+    fn exact_theta(&self, input_domain: &[Vec<i32>]) -> Option<f64> {
+        let squares = self.fault_points.iter().zip(&self.deltas);
+        let overlapping = squares.clone().enumerate().any(|(i, (p1, &d1))| {
+            squares
+                .clone()
+                .skip(i + 1)
+                .any(|(p2, &d2)| Self::is_overlap(p1, d1, p2, d2))
+        });
+        (!overlapping).then(|| {
+            squares
+                .map(|(p, delta)| {
+                    let bb = p
+                        .coordinates
+                        .iter()
+                        .map(|&c| vec![c as f64, c as f64 + delta])
+                        .collect::<Vec<_>>();
+                    box_theta(&bb, input_domain)
+                })
+                .sum()
+        })
+    }
+
+    fn bounding_box(&self) -> Option<Vec<Vec<f64>>> {
+        let n_dims = self.input_domain.len();
+        Some(
+            (0..n_dims)
+                .map(|i| {
+                    let lower = self
+                        .fault_points
+                        .iter()
+                        .map(|p| p.coordinates[i] as f64)
+                        .fold(f64::INFINITY, f64::min);
+                    let upper = self
+                        .fault_points
+                        .iter()
+                        .zip(&self.deltas)
+                        .map(|(p, delta)| p.coordinates[i] as f64 + delta)
+                        .fold(f64::NEG_INFINITY, f64::max);
+                    vec![lower, upper]
+                })
+                .collect(),
+        )
+    }
+}
+
+/// Splits the failure rate `theta` into `n_regions` parts.
+fn region_thetas<R: Rng + ?Sized>(
+    theta: f64,
+    n_regions: usize,
+    sizes: &SizeDistribution,
+    rng: &mut R,
+) -> Vec<f64> {
+    let weights = match *sizes {
+        SizeDistribution::Equal => vec![1.0; n_regions],
+        // Normalised exponential variables are uniformly distributed on the simplex
+        SizeDistribution::RandomSplit => (0..n_regions)
+            .map(|_| -(1.0 - rng.random::<f64>()).ln())
+            .collect(),
+        SizeDistribution::PowerLaw { exponent } => (1..=n_regions)
+            .map(|i| (i as f64).powf(-exponent))
+            .collect(),
+    };
+    let total = weights.iter().sum::<f64>();
+    weights.iter().map(|w| theta * w / total).collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn test_point_patterns() {
+        let mut rng = StdRng::seed_from_u64(23);
+        let input_domain = vec![vec![0, 1000], vec![0, 1000], vec![0, 1000]];
+
+        let pattern = PointPattern {
+            n_regions: 10,
+            sizes: SizeDistribution::PowerLaw { exponent: 1.5 },
+            cluster_spread: Some(0.5),
+            overlap: OverlapPolicy::Disjoint,
+        };
+        let fzps =
+            FaultZonePointSquare::with_pattern(&input_domain, 0.01, &pattern, &mut rng).unwrap();
+        assert_eq!(fzps.fault_points.len(), 10);
+        let volume = fzps.deltas.iter().map(|d| d.powi(3)).sum::<f64>();
+        assert!((volume / 1e9 - 0.01).abs() < 1e-9);
+        let bb = fzps.bounding_box().unwrap();
+        assert!(bb.iter().all(|b| b[1] - b[0] <= 500.0 + 1e-3));
+
+        // Regions wider than half of the cluster always overlap
+        let crowded = PointPattern {
+            n_regions: 4,
+            cluster_spread: Some(0.6),
+            ..PointPattern::default()
+        };
+        assert_eq!(
+            FaultZonePointSquare::with_pattern(&input_domain, 0.256, &crowded, &mut rng).err(),
+            Some(FaultZoneError::NoLayout {
+                n_regions: 4,
+                attempts: MAX_PLACEMENT_ATTEMPTS
+            })
+        );
+        assert_eq!(
+            FaultZonePointSquare::with_pattern(&input_domain, 0.9, &crowded, &mut rng).err(),
+            Some(FaultZoneError::DoesNotFit { theta: 0.9 })
+        );
+    }
+
+    #[test]
+    fn test_reject_invalid_patterns() {
+        let mut rng = StdRng::seed_from_u64(31);
+        let input_domain = vec![vec![0, 1000], vec![0, 1000]];
+
+        let patterns = [
+            (
+                PointPattern {
+                    n_regions: 0,
+                    ..PointPattern::default()
+                },
+                "n_regions",
+            ),
+            (
+                PointPattern {
+                    cluster_spread: Some(0.0),
+                    ..PointPattern::default()
+                },
+                "cluster_spread",
+            ),
+            (
+                PointPattern {
+                    cluster_spread: Some(1.5),
+                    ..PointPattern::default()
+                },
+                "cluster_spread",
+            ),
+            (
+                PointPattern {
+                    sizes: SizeDistribution::PowerLaw { exponent: f64::NAN },
+                    ..PointPattern::default()
+                },
+                "exponent",
+            ),
+            (
+                PointPattern {
+                    sizes: SizeDistribution::PowerLaw {
+                        exponent: f64::INFINITY,
+                    },
+                    ..PointPattern::default()
+                },
+                "exponent",
+            ),
+        ];
+        for (pattern, parameter) in patterns {
+            match FaultZonePointSquare::with_pattern(&input_domain, 0.01, &pattern, &mut rng) {
+                Err(FaultZoneError::InvalidParameter { name, .. }) => assert_eq!(name, parameter),
+                result => panic!("{pattern:?} gave {result:?}"),
+            }
+        }
+    }
 }
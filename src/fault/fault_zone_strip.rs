@@ -1,105 +1,182 @@
 use rand::Rng;
 
-use crate::util::point::Point;
+use crate::util::{
+    geometry::{random_unit_vector, unit_cube_halfspace_volume},
+    point::Point,
+};
 
+use super::fault_zone::{FaultZone, FaultZoneError};
 
+/// Smallest magnitude of a normal component relative to `1 / sqrt(d)`. Normals almost parallel to
+/// a coordinate axis hyperplane are redrawn, as they make the volume computation inaccurate.
+const MIN_NORMAL_COMPONENT: f64 = 0.1;
+
+/// Number of bisection steps when searching the thickness of the strip
+const N_BISECTIONS: usize = 60;
+
+/// A strip, i.e. the slab between two parallel hyperplanes, with a random normal.
+///
+/// The strip is defined in input domain coordinates scaled to the unit hypercube,
+/// `u_i = (x_i - lower_i) / (upper_i - lower_i)`, as `lower_offset <= normal . u <= upper_offset`.
+/// Its thickness is chosen so that it covers exactly `theta` of the input domain.
 #[derive(Debug)]
 pub struct FaultZoneStrip<'this> {
     pub input_domain: &'this [Vec<i32>],
-    pub edge: i32,
-    pub above_line_delta: f64,
-    pub below_line_delta: f64,
-    pub ratio: f64,
+    /// Unit normal of the strip in scaled coordinates
+    pub normal: Vec<f64>,
+    pub lower_offset: f64,
+    pub upper_offset: f64,
     pub theta: f64,
 }
 
 impl<'this> FaultZoneStrip<'this> {
-    pub fn find_target(&self, p: &Point) -> bool {
-        (p.coordinates[1] - self.ratio as f32 * p.coordinates[0] >= self.below_line_delta as f32)
-            && (p.coordinates[1] - self.ratio as f32 * p.coordinates[0] <= self.above_line_delta as f32)
+    fn scaled_offset(&self, p: &Point) -> f64 {
+        (0..p.n)
+            .map(|i| {
+                let (lower, upper) = (self.input_domain[i][0], self.input_domain[i][1]);
+                self.normal[i] * (p.coordinates[i] as f64 - lower as f64) / (upper - lower) as f64
+            })
+            .sum()
     }
 
-    pub fn get_theta(&self) -> f64 {
-        self.theta
-    }
+    /// Creates a strip covering `area` of the input domain. The centre of the strip is drawn from
+    /// the central `rate` fraction of the range of `normal . u` over the input domain, so that a
+    /// lower rate keeps the strip away from the corners.
+    ///
+    /// Unlike the original 2D strips, where `rate` was the fraction of the domain edges near a
+    /// corner that the ends of the strip were drawn from, `rate` no longer depends on the corners.
+    ///
+    /// Fails if `area` is not in (0, 1) or `rate` is not in [0, 1].
+    pub fn new<R: Rng + ?Sized>(
+        boundary: &'this [Vec<i32>],
+        area: f64,
+        rate: f64,
+        rng: &mut R,
+    ) -> Result<Self, FaultZoneError> {
+        if !(area > 0.0 && area.is_finite()) {
+            return Err(FaultZoneError::InvalidTheta { theta: area });
+        }
+        if area >= 1.0 {
+            return Err(FaultZoneError::DoesNotFit { theta: area });
+        }
+        if !(0.0..=1.0).contains(&rate) {
+            return Err(FaultZoneError::InvalidParameter {
+                name: "rate",
+                value: rate,
+            });
+        }
 
-    pub fn new(boundary: &'this [Vec<i32>], area: f64, rate: f64) -> FaultZoneStrip<'this> {
-        let mut fzs = Self {
-            input_domain: boundary,
-            edge: boundary[0][1] - boundary[0][0],
-            above_line_delta: 0.0,
-            below_line_delta: 0.0,
-            ratio: 0.0,
-            theta: area,
+        let n = boundary.len();
+        let min_component = MIN_NORMAL_COMPONENT / (n as f64).sqrt();
+        let normal = loop {
+            let normal = random_unit_vector(n, rng);
+            if normal.iter().all(|c| c.abs() >= min_component) {
+                break normal;
+            }
         };
 
-        let mut rng = rand::rng();
-        let line_location = rng.random_range(0..3);
-
-        let (mut p1x, mut p1y, mut p2x, mut p2y, mut p3x, mut p3y, mut p4x, mut p4y) =
-            (0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0);
-
-        if line_location == 0 {
-            loop {
-                p1x = -5000.0;
-                p2x = -5000.0;
-                p2y = -5000.0 + (10000.0 * rate * rng.random::<f64>());
-                p3y = 5000.0;
-                p4x = (-5000.0 + (10000.0 * (1.0 - rate))) + (10000.0 * rate * rng.random::<f64>());
-                p4y = 5000.0;
-
-                let big_triangle_area = (5000.0 - p2y) * (p4x + 5000.0) / 2.0;
-                fzs.ratio = (p4y - p2y) / (p4x - p2x);
-                let temp = 2.0 * (big_triangle_area - 10000.0 * 10000.0 * area) / fzs.ratio;
-                p3x = temp.sqrt() - 5000.0;
-                p1y = 5000.0 - fzs.ratio * (p3x + 5000.0);
-
-                if (p3x >= (-5000.0 + (10000.0 * (1.0 - rate))))
-                    && (p1y <= (-5000.0 + 10000.0 * rate))
-                {
-                    break;
-                }
-            }
-        } else if line_location == 1 {
-            loop {
-                p1x = -5000.0;
-                p2x = -5000.0;
-                p2y = -5000.0 + (10000.0 * rng.random::<f64>());
-                p3x = 5000.0;
-                p4x = 5000.0;
-                p4y = -5000.0 + (10000.0 * rng.random::<f64>());
-                p1y = p2y + 10000.0 * area;
-                p3y = p4y + 10000.0 * area;
-                fzs.ratio = (p4y - p2y) / (p4x - p2x);
-                if p1y <= 5000.0 && p3y <= 5000.0 {
-                    break;
-                }
-            }
-        } else {
-            loop {
-                p1x = -5000.0;
-                p1y = (-5000.0 + (10000.0 * (1.0 - rate))) + (10000.0 * rate * rng.random::<f64>());
-                p2x = -5000.0;
-                p3x = (-5000.0 + (10000.0 * (1.0 - rate))) + (10000.0 * rate * rng.random::<f64>());
-                p3y = -5000.0;
-                p4y = -5000.0;
-
-                fzs.ratio = (p3y - p1y) / (p3x - p1x);
-                let big_triangle_area = (p1y + 5000.0) * (p3x + 5000.0) / 2.0;
-                let temp = 2.0 * (10000.0 * 10000.0 * area - big_triangle_area) / fzs.ratio;
-                p4x = temp.sqrt() - 5000.0;
-                p2y = -fzs.ratio * (p4x + 5000.0) - 5000.0;
-
-                if (p4x >= (-5000.0 + (10000.0 * (1.0 - rate))))
-                    && (p2y >= (-5000.0 + (10000.0 * (1.0 - rate))))
-                {
-                    break;
-                }
+        let (weights, base) = unit_cube_weights(&normal);
+        let range = weights.iter().sum::<f64>();
+        let below = |offset: f64| unit_cube_halfspace_volume(&weights, offset - base);
+
+        let centre = base + range * (0.5 + rate * (rng.random::<f64>() - 0.5));
+        let strip_volume =
+            |half_width: f64| below(centre + half_width) - below(centre - half_width);
+
+        let (mut lo, mut hi) = (0.0, range);
+        for _ in 0..N_BISECTIONS {
+            let mid = (lo + hi) / 2.0;
+            if strip_volume(mid) < area {
+                lo = mid;
+            } else {
+                hi = mid;
             }
         }
+        let half_width = (lo + hi) / 2.0;
+
+        Ok(Self {
+            input_domain: boundary,
+            normal,
+            lower_offset: centre - half_width,
+            upper_offset: centre + half_width,
+            theta: area,
+        })
+    }
+}
 
-        fzs.above_line_delta = p1y - fzs.ratio * p1x;
-        fzs.below_line_delta = p4y - fzs.ratio * p4x;
-        fzs
+impl FaultZone for FaultZoneStrip<'_> {
+    fn find_target(&self, p: &Point) -> bool {
+        let offset = self.scaled_offset(p);
+        offset >= self.lower_offset && offset <= self.upper_offset
+    }
+
+    fn get_theta(&self) -> f64 {
+        self.theta
+    }
+
+    /// Exact for the input domain the strip was generated for, as the strip is defined relative
+    /// to it.
+    fn exact_theta(&self, input_domain: &[Vec<i32>]) -> Option<f64> {
+        if input_domain != self.input_domain {
+            return None;
+        }
+        let (weights, base) = unit_cube_weights(&self.normal);
+        let below = |offset: f64| unit_cube_halfspace_volume(&weights, offset - base);
+        Some(below(self.upper_offset) - below(self.lower_offset))
+    }
+}
+
+/// Returns the positive `weights` and the `base` offset for which
+/// `normal . u = base + weights . u'`, where `u'_i = 1 - u_i` for negative normal components.
+fn unit_cube_weights(normal: &[f64]) -> (Vec<f64>, f64) {
+    let weights = normal.iter().map(|c| c.abs()).collect();
+    let base = normal.iter().filter(|&&c| c < 0.0).sum();
+    (weights, base)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::fault::fault_zone::assert_covers_theta;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn test_strip_covers_theta_of_input_domain() {
+        let mut rng = StdRng::seed_from_u64(11);
+
+        for n_dims in [2usize, 6, 15] {
+            let input_domain = (0..n_dims)
+                .map(|i| vec![-5000 + 1000 * i as i32, 100 + 400 * i as i32])
+                .collect::<Vec<_>>();
+            let theta = 0.05;
+            let strip = FaultZoneStrip::new(&input_domain, theta, 0.9, &mut rng).unwrap();
+            assert_covers_theta(&strip, &input_domain, theta, &mut rng);
+        }
+    }
+
+    #[test]
+    fn test_reject_invalid_strips() {
+        let mut rng = StdRng::seed_from_u64(37);
+        let input_domain = vec![vec![-5000, 5000], vec![0, 1000]];
+
+        for theta in [0.0, -0.1, f64::NAN] {
+            assert!(matches!(
+                FaultZoneStrip::new(&input_domain, theta, 0.9, &mut rng),
+                Err(FaultZoneError::InvalidTheta { .. })
+            ));
+        }
+        for theta in [1.0, 1.5, f64::INFINITY] {
+            assert!(matches!(
+                FaultZoneStrip::new(&input_domain, theta, 0.9, &mut rng),
+                Err(FaultZoneError::InvalidTheta { .. } | FaultZoneError::DoesNotFit { .. })
+            ));
+        }
+        for rate in [-0.5, 1.5, f64::NAN] {
+            assert!(matches!(
+                FaultZoneStrip::new(&input_domain, 0.01, rate, &mut rng),
+                Err(FaultZoneError::InvalidParameter { name: "rate", .. })
+            ));
+        }
+        assert!(FaultZoneStrip::new(&input_domain, 0.01, 0.0, &mut rng).is_ok());
     }
 }
@@ -1,3 +1,5 @@
 pub mod util;
 pub mod fault;
 pub mod art;
+pub mod sut;
+pub mod experiment;
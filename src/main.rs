@@ -1,3 +1,251 @@
-fn main() {
-    println!("Hello, world!");
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
+
+use clap::{error::ErrorKind, CommandFactory, Parser, Subcommand};
+
+use rart::experiment::{
+    checkpoint::Checkpoint,
+    effectiveness::{run_effectiveness, EffectivenessConfig},
+    efficiency::{run_efficiency, EfficiencyConfig},
+    raw::RawFormat,
+    Algorithm, Domain, ExperimentConfig, Shape,
+};
+
+/// Adaptive random testing algorithms and experiments
+#[derive(Debug, Parser)]
+#[command(version)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Run the experiments of a TOML or JSON configuration file
+    Run {
+        /// Path of the configuration file
+        config: PathBuf,
+    },
+    /// Measure how many test cases each algorithm needs to find a fault zone
+    Effectiveness {
+        /// Algorithms to compare: rt, lhs, fscs, naive-kdfc, semi-bal-kdfc, lim-bal-kdfc,
+        /// approx-kdfc, rrt, b-art, rp-art, halton, sobol
+        #[arg(short, long, value_delimiter = ',', default_values = ["rt", "lhs"])]
+        algorithms: Vec<Algorithm>,
+        /// Error bounds of approx-kdfc to sweep, each run as an additional algorithm
+        #[arg(long, value_delimiter = ',')]
+        epsilons: Vec<f64>,
+        /// Numbers of input domain dimensions
+        #[arg(short, long, value_delimiter = ',', default_values_t = [6])]
+        dims: Vec<usize>,
+        /// Failure rates, i.e. fault zone sizes relative to the input domain
+        #[arg(short, long, value_delimiter = ',', default_values_t = [0.01, 0.005, 0.002, 0.001, 0.0005, 0.0002, 0.0001])]
+        failure_rates: Vec<f64>,
+        /// Fault zone shapes: block, strip, point, ball, ellipsoid
+        #[arg(short, long, value_delimiter = ',', default_values = ["block", "strip", "point"])]
+        shapes: Vec<Shape>,
+        /// Number of fault zones generated per configuration
+        #[arg(short = 'n', long, default_value_t = 1000)]
+        iterations: usize,
+        /// Number of runs per algorithm on each fault zone
+        #[arg(short, long, default_value_t = 10)]
+        repeats: usize,
+        /// Master seed, random if not given
+        #[arg(long)]
+        seed: Option<u64>,
+        #[arg(short, long, default_value = "test-results/effectiveness")]
+        output_dir: PathBuf,
+        /// Number of worker threads, the available parallelism if not given
+        #[arg(short, long)]
+        threads: Option<usize>,
+        /// Measure the failure rate of every fault zone and stop if it deviates from the nominal
+        /// rate by more than this relative tolerance
+        #[arg(long, value_name = "TOLERANCE")]
+        validate_theta: Option<f64>,
+        /// Number of test cases of the P-measure and E-measure, 1 / failure rate if not given
+        #[arg(long)]
+        n_tests: Option<usize>,
+        /// Format of the raw per-trial results: csv, jsonl
+        #[arg(long, default_value_t = RawFormat::Csv)]
+        raw_format: RawFormat,
+        /// Save completed trials into this file and skip the ones already in it, so that an
+        /// interrupted run can be resumed with the same command. The seed is taken from the file
+        /// if not given.
+        #[arg(long, value_name = "FILE")]
+        checkpoint: Option<PathBuf>,
+    },
+    /// Measure how long each algorithm takes to generate test cases
+    Efficiency {
+        /// Algorithms to compare: rt, lhs, fscs, naive-kdfc, semi-bal-kdfc, lim-bal-kdfc,
+        /// approx-kdfc, rrt, b-art, rp-art, halton, sobol
+        #[arg(short, long, value_delimiter = ',', default_values = ["lhs", "fscs", "naive-kdfc", "semi-bal-kdfc", "lim-bal-kdfc"])]
+        algorithms: Vec<Algorithm>,
+        /// Numbers of input domain dimensions
+        #[arg(short, long, value_delimiter = ',', default_values_t = [2])]
+        dims: Vec<usize>,
+        /// Numbers of test cases to generate
+        #[arg(short, long, value_delimiter = ',', default_values_t = [100, 200, 500, 1000, 2000, 5000, 10000, 15000, 20000])]
+        counts: Vec<usize>,
+        /// Number of timed runs per algorithm and number of test cases
+        #[arg(short = 'n', long, default_value_t = 1000)]
+        runs: usize,
+        /// Master seed, random if not given
+        #[arg(long)]
+        seed: Option<u64>,
+        #[arg(short, long, default_value = "test-results")]
+        output_dir: PathBuf,
+        /// Format of the raw per-run results: csv, jsonl
+        #[arg(long, default_value_t = RawFormat::Csv)]
+        raw_format: RawFormat,
+    },
+    /// Print test cases of an algorithm as CSV
+    Generate {
+        /// Algorithm: rt, lhs, fscs, naive-kdfc, semi-bal-kdfc, lim-bal-kdfc, approx-kdfc, rrt,
+        /// b-art, rp-art, halton, sobol
+        #[arg(short, long, default_value = "fscs")]
+        algorithm: Algorithm,
+        /// Number of input domain dimensions
+        #[arg(short, long, default_value_t = 2)]
+        dims: usize,
+        /// Number of test cases to generate
+        #[arg(short, long, default_value_t = 100)]
+        count: usize,
+        /// Seed, random if not given, in which case it is printed to stderr
+        #[arg(long)]
+        seed: Option<u64>,
+    },
+}
+
+/// Exits with a usage error if no dimension count is given, one of them is 0 or one of the
+/// algorithms does not support one of them.
+fn check_dims(algorithms: &[Algorithm], dims: &[usize]) {
+    if dims.is_empty() {
+        Cli::command()
+            .error(ErrorKind::ValueValidation, "at least one dimension count is required")
+            .exit();
+    }
+    if dims.contains(&0) {
+        Cli::command()
+            .error(ErrorKind::ValueValidation, "the number of dimensions must be at least 1")
+            .exit();
+    }
+    for algorithm in algorithms {
+        for &n_dims in dims {
+            if let Err(e) = algorithm.check_dims(n_dims) {
+                Cli::command().error(ErrorKind::ValueValidation, e).exit();
+            }
+        }
+    }
+}
+
+fn main() -> std::io::Result<()> {
+    match Cli::parse().command {
+        Command::Run { config } => {
+            let config = ExperimentConfig::from_file(&config)?;
+            if let Some(effectiveness) = config.effectiveness {
+                let summary_path = run_effectiveness(&effectiveness)?;
+                println!("results saved to {}", summary_path.display());
+            }
+            if let Some(efficiency) = config.efficiency {
+                run_efficiency(&efficiency)?;
+            }
+        }
+        Command::Effectiveness {
+            mut algorithms,
+            epsilons,
+            dims,
+            failure_rates,
+            shapes,
+            iterations,
+            repeats,
+            seed,
+            output_dir,
+            threads,
+            validate_theta,
+            n_tests,
+            raw_format,
+            checkpoint,
+        } => {
+            let seed = match (seed, &checkpoint) {
+                (Some(seed), _) => seed,
+                (None, Some(path)) => Checkpoint::seed(path)?.unwrap_or_else(rand::random),
+                (None, None) => rand::random(),
+            };
+            check_dims(&algorithms, &dims);
+            algorithms.extend(
+                epsilons
+                    .into_iter()
+                    .map(|epsilon| Algorithm::ApproxKdfc { epsilon }),
+            );
+            let config = EffectivenessConfig {
+                algorithms,
+                shapes,
+                area_sizes: failure_rates,
+                domains: dims.into_iter().map(Domain::cube).collect(),
+                n_iter: iterations,
+                n_repeat_fault_zone: repeats,
+                seed,
+                output_dir,
+                n_threads: threads,
+                validate_theta,
+                n_tests,
+                raw_format,
+                checkpoint,
+            };
+            let summary_path = run_effectiveness(&config)?;
+            println!("results saved to {}", summary_path.display());
+        }
+        Command::Efficiency {
+            algorithms,
+            dims,
+            counts,
+            runs,
+            seed,
+            output_dir,
+            raw_format,
+        } => {
+            check_dims(&algorithms, &dims);
+            let config = EfficiencyConfig {
+                algorithms,
+                domains: dims.into_iter().map(Domain::cube).collect(),
+                point_nums: counts,
+                n_runs: runs,
+                seed: seed.unwrap_or_else(rand::random),
+                output_dir,
+                raw_format,
+            };
+            run_efficiency(&config)?;
+        }
+        Command::Generate {
+            algorithm,
+            dims,
+            count,
+            seed,
+        } => {
+            check_dims(&[algorithm], &[dims]);
+            let bound = Domain::cube(dims).bounds();
+            let mut generator = algorithm.generator(&bound);
+            // stdout carries the test cases, so a random seed is reported on stderr
+            let seed = seed.unwrap_or_else(|| {
+                let seed = rand::random();
+                eprintln!("seed: {seed}");
+                seed
+            });
+            generator.reseed(seed);
+
+            let mut out = BufWriter::new(std::io::stdout().lock());
+            for p in generator.by_ref().take(count) {
+                let line = p
+                    .coordinates
+                    .iter()
+                    .map(|c| c.to_string())
+                    .collect::<Vec<_>>()
+                    .join(",");
+                writeln!(out, "{line}")?;
+            }
+            out.flush()?;
+        }
+    }
+
+    Ok(())
 }
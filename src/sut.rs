@@ -0,0 +1,176 @@
+use std::any::Any;
+use std::panic::{self, AssertUnwindSafe};
+use std::time::{Duration, Instant};
+
+use crate::{art::art_generator::ArtGenerator, util::point::Point};
+
+/// Verdict of an oracle on a single test input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Pass,
+    Fail,
+}
+
+/// Limit on the amount of testing done by `run_sut`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Budget {
+    /// Maximum number of executed test inputs
+    Count(usize),
+    /// Maximum wall time spent generating and executing test inputs
+    Time(Duration),
+}
+
+/// The first failure found by `run_sut`.
+#[derive(Debug, Clone)]
+pub struct Failure {
+    pub input: Point,
+    /// Number of test inputs executed until the failure was found, including the failing one
+    pub f_measure: usize,
+    /// Panic message when the system under test panicked instead of returning `Outcome::Fail`
+    pub panic_message: Option<String>,
+}
+
+/// Result of `run_sut`.
+#[derive(Debug, Clone)]
+pub struct SutRun {
+    /// The first failure, or `None` when the budget ran out first
+    pub failure: Option<Failure>,
+    /// All executed test inputs in execution order
+    pub executed: Vec<Point>,
+    pub elapsed: Duration,
+}
+
+impl SutRun {
+    pub fn first_failing_input(&self) -> Option<&Point> {
+        self.failure.as_ref().map(|f| &f.input)
+    }
+
+    pub fn f_measure(&self) -> Option<usize> {
+        self.failure.as_ref().map(|f| f.f_measure)
+    }
+}
+
+/// Tests a system under test adaptively with test inputs from `generator`.
+///
+/// The oracle runs the system under test on an input and judges the outcome. Panics in the oracle
+/// are caught and treated as failures. Testing stops at the first failure or when the budget runs
+/// out. The generator is reset before the run.
+pub fn run_sut<G, F>(generator: &mut G, oracle: F, budget: Budget) -> SutRun
+where
+    G: ArtGenerator + ?Sized,
+    F: Fn(&[f64]) -> Outcome,
+{
+    generator.reset();
+
+    let start = Instant::now();
+    let mut executed = Vec::new();
+    let mut failure = None;
+
+    while !is_exhausted(budget, executed.len(), start) {
+        let test_case = generator.next_test_case();
+        let input = test_case
+            .coordinates
+            .iter()
+            .map(|&c| c as f64)
+            .collect::<Vec<_>>();
+        let result = panic::catch_unwind(AssertUnwindSafe(|| oracle(&input)));
+        executed.push(test_case);
+
+        let panic_message = match result {
+            Ok(Outcome::Pass) => continue,
+            Ok(Outcome::Fail) => None,
+            Err(payload) => Some(panic_message(payload.as_ref())),
+        };
+
+        failure = Some(Failure {
+            input: executed[executed.len() - 1].clone(),
+            f_measure: executed.len(),
+            panic_message,
+        });
+        break;
+    }
+
+    SutRun {
+        failure,
+        executed,
+        elapsed: start.elapsed(),
+    }
+}
+
+fn is_exhausted(budget: Budget, n_executed: usize, start: Instant) -> bool {
+    match budget {
+        Budget::Count(max_count) => n_executed >= max_count,
+        Budget::Time(max_time) => start.elapsed() >= max_time,
+    }
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::art::{fscs_art::FscsArt, rt::Rt};
+
+    #[test]
+    fn test_run_sut_finds_failure() {
+        let input_domain = vec![vec![-100, 100], vec![-100, 100]];
+        let mut fscs = FscsArt::with_bound(&input_domain);
+        fscs.reseed(1);
+
+        let run = run_sut(
+            &mut fscs,
+            |x| {
+                if x[0] > 50.0 && x[1] > 50.0 {
+                    Outcome::Fail
+                } else {
+                    Outcome::Pass
+                }
+            },
+            Budget::Count(10_000),
+        );
+
+        let failure = run.failure.expect("failure should be found");
+        assert!(failure.input.coordinates[0] > 50.0 && failure.input.coordinates[1] > 50.0);
+        assert_eq!(failure.f_measure, run.executed.len());
+        assert!(failure.panic_message.is_none());
+    }
+
+    #[test]
+    fn test_run_sut_catches_panics() {
+        let input_domain = vec![vec![0, 10]];
+        let mut rt = Rt::with_bound(&input_domain);
+
+        let run = run_sut(
+            &mut rt,
+            |x| {
+                assert!(x[0] < 5.0, "input too large");
+                Outcome::Pass
+            },
+            Budget::Time(Duration::from_secs(10)),
+        );
+
+        let failure = run.failure.expect("panic should be caught as a failure");
+        assert!(failure.input.coordinates[0] >= 5.0);
+        assert_eq!(failure.panic_message.as_deref(), Some("input too large"));
+    }
+
+    #[test]
+    fn test_run_sut_stops_at_budget() {
+        let input_domain = vec![vec![0, 10], vec![0, 10]];
+        let mut rt = Rt::with_bound(&input_domain);
+
+        let run = run_sut(&mut rt, |_| Outcome::Pass, Budget::Count(100));
+
+        assert!(run.failure.is_none());
+        assert_eq!(run.executed.len(), 100);
+        assert_eq!(run.f_measure(), None);
+    }
+}
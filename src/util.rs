@@ -1,3 +1,6 @@
-// add util/node.rs and util/point.rs
-pub mod node;
+// add util/geometry.rs, util/kd_tree.rs, util/point.rs, util/seed.rs and util/stats.rs
+pub mod geometry;
+pub mod kd_tree;
 pub mod point;
+pub mod seed;
+pub mod stats;
@@ -0,0 +1,126 @@
+use std::f64::consts::PI;
+
+use rand::Rng;
+
+/// Returns the volume of the unit ball in `n_dims` dimensions, `pi^(n/2) / gamma(n/2 + 1)`.
+pub fn unit_ball_volume(n_dims: usize) -> f64 {
+    // V(0) = 1, V(1) = 2 and V(n) = 2 pi / n * V(n - 2)
+    let (mut volume, mut n) = if n_dims.is_multiple_of(2) {
+        (1.0, 2)
+    } else {
+        (2.0, 3)
+    };
+    while n <= n_dims {
+        volume *= 2.0 * PI / n as f64;
+        n += 2;
+    }
+    volume
+}
+
+/// Returns the volume of the hyper-rectangle `bound`.
+pub fn domain_volume(bound: &[Vec<i32>]) -> f64 {
+    bound
+        .iter()
+        .map(|b| (b[1] - b[0]) as f64)
+        .product::<f64>()
+}
+
+/// Returns a uniformly distributed random direction in `n_dims` dimensions.
+pub fn random_unit_vector<R: Rng + ?Sized>(n_dims: usize, rng: &mut R) -> Vec<f64> {
+    loop {
+        // Standard normal coordinates by the Box-Muller transform
+        let v = (0..n_dims)
+            .map(|_| {
+                let u1 = 1.0 - rng.random::<f64>();
+                let u2 = rng.random::<f64>();
+                (-2.0 * u1.ln()).sqrt() * (2.0 * PI * u2).cos()
+            })
+            .collect::<Vec<_>>();
+        let norm = v.iter().map(|x| x * x).sum::<f64>().sqrt();
+        if norm > 0.0 {
+            return v.into_iter().map(|x| x / norm).collect();
+        }
+    }
+}
+
+/// Returns a uniformly distributed random rotation in `n_dims` dimensions as the orthonormal
+/// basis vectors it maps the coordinate axes to.
+pub fn random_rotation<R: Rng + ?Sized>(n_dims: usize, rng: &mut R) -> Vec<Vec<f64>> {
+    let mut basis: Vec<Vec<f64>> = Vec::with_capacity(n_dims);
+    while basis.len() < n_dims {
+        // Gram-Schmidt orthogonalisation of random directions
+        let mut v = random_unit_vector(n_dims, rng);
+        for b in &basis {
+            let dot = v.iter().zip(b).map(|(x, y)| x * y).sum::<f64>();
+            v.iter_mut().zip(b).for_each(|(x, y)| *x -= dot * y);
+        }
+        let norm = v.iter().map(|x| x * x).sum::<f64>().sqrt();
+        if norm > 1e-6 {
+            basis.push(v.into_iter().map(|x| x / norm).collect());
+        }
+    }
+    basis
+}
+
+/// Returns the volume of the part of the unit hypercube below the hyperplane `a . u = t`, for
+/// positive weights `a`.
+///
+/// Uses `V(t) = sum_v (-1)^|v| (t - a . v)_+^d / (d! prod a)` over the vertices `v` of the
+/// hypercube, so the cost grows as `2^d` and the accuracy drops for very small weights.
+pub fn unit_cube_halfspace_volume(a: &[f64], t: f64) -> f64 {
+    let total = a.iter().sum::<f64>();
+    if t <= 0.0 {
+        return 0.0;
+    }
+    if t >= total {
+        return 1.0;
+    }
+    // The smaller side has fewer vertices below the hyperplane and less cancellation
+    if t > total / 2.0 {
+        return 1.0 - unit_cube_halfspace_volume(a, total - t);
+    }
+
+    let n_dims = a.len();
+    let scale = (1..=n_dims).map(|i| i as f64).product::<f64>() * a.iter().product::<f64>();
+
+    // Depth-first over the vertices, skipping subtrees that are entirely above the hyperplane
+    let mut sum = 0.0;
+    let mut stack = vec![(0, 0.0, 1.0)];
+    while let Some((i, offset, sign)) = stack.pop() {
+        if offset >= t {
+            continue;
+        }
+        if i == n_dims {
+            sum += sign * (t - offset).powi(n_dims as i32);
+            continue;
+        }
+        stack.push((i + 1, offset, sign));
+        stack.push((i + 1, offset + a[i], -sign));
+    }
+    (sum / scale).clamp(0.0, 1.0)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_unit_ball_volume() {
+        let expected = [1.0, 2.0, PI, 4.0 / 3.0 * PI, PI * PI / 2.0, 8.0 / 15.0 * PI * PI];
+        for (n_dims, volume) in expected.iter().enumerate() {
+            assert!((unit_ball_volume(n_dims) - volume).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_unit_cube_halfspace_volume() {
+        // Triangle below x + y = 1/2 and the complement of the corner above x + y + z = 5/2
+        assert!((unit_cube_halfspace_volume(&[1.0, 1.0], 0.5) - 0.125).abs() < 1e-12);
+        assert!((unit_cube_halfspace_volume(&[1.0, 2.0], 1.0) - 0.25).abs() < 1e-12);
+        assert!((unit_cube_halfspace_volume(&[1.0, 1.0, 1.0], 2.5) - 47.0 / 48.0).abs() < 1e-12);
+        // Half of the hypercube is below the hyperplane through its centre
+        let a = [0.3, 0.1, 0.7, 0.2, 0.5, 0.4, 0.6, 0.25, 0.15, 0.35, 0.45, 0.55];
+        let centre = a.iter().sum::<f64>() / 2.0;
+        assert!((unit_cube_halfspace_volume(&a, centre) - 0.5).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,608 @@
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+/// Index of a node in a `KdTree`
+pub type NodeId = usize;
+
+/// A point of a `KdTree` found by a query, with its distance to the query point
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Neighbour<'t> {
+    pub id: NodeId,
+    pub point: &'t [f64],
+    pub distance: f64,
+}
+
+/// A node of a `KdTree`. Its point and region are stored in the flat arrays of the tree.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KdNode {
+    /// Dimension along which the point of the node splits its region
+    pub split: usize,
+    /// Child with the points below the split value
+    pub left: Option<NodeId>,
+    /// Child with the points at or above the split value
+    pub right: Option<NodeId>,
+    pub parent: Option<NodeId>,
+    /// 1 for the root
+    pub depth: usize,
+}
+
+/// A k-d tree whose nodes live in one arena and refer to each other, including their parents, by
+/// index.
+///
+/// The coordinates of the points and the bounds of the regions of the nodes are stored in flat
+/// arrays with `n_dims` values per node, so that searches walk contiguous memory and the tree can
+/// be cloned or cleared without allocating per node.
+#[derive(Debug, Clone, Default)]
+pub struct KdTree {
+    n_dims: usize,
+    nodes: Vec<KdNode>,
+    coordinates: Vec<f64>,
+    lower: Vec<f64>,
+    upper: Vec<f64>,
+    /// Region of the root, i.e. the input domain
+    domain_lower: Vec<f64>,
+    domain_upper: Vec<f64>,
+}
+
+impl KdTree {
+    /// Creates an empty tree over the input domain, given as the `[lower, upper]` bounds of each
+    /// dimension.
+    pub fn new(input_domain: &[Vec<i32>]) -> Self {
+        Self {
+            n_dims: input_domain.len(),
+            domain_lower: input_domain.iter().map(|b| b[0] as f64).collect(),
+            domain_upper: input_domain.iter().map(|b| b[1] as f64).collect(),
+            ..Self::default()
+        }
+    }
+
+    pub fn n_dims(&self) -> usize {
+        self.n_dims
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Removes all points, keeping the allocated storage for reuse.
+    pub fn clear(&mut self) {
+        self.nodes.clear();
+        self.coordinates.clear();
+        self.lower.clear();
+        self.upper.clear();
+    }
+
+    /// Removes all points and replaces the input domain, keeping the allocated storage for reuse.
+    pub fn reset(&mut self, input_domain: &[Vec<i32>]) {
+        self.clear();
+        self.n_dims = input_domain.len();
+        self.domain_lower.clear();
+        self.domain_lower
+            .extend(input_domain.iter().map(|b| b[0] as f64));
+        self.domain_upper.clear();
+        self.domain_upper
+            .extend(input_domain.iter().map(|b| b[1] as f64));
+    }
+
+    pub fn root(&self) -> Option<NodeId> {
+        (!self.nodes.is_empty()).then_some(0)
+    }
+
+    pub fn node(&self, id: NodeId) -> &KdNode {
+        &self.nodes[id]
+    }
+
+    pub fn point(&self, id: NodeId) -> &[f64] {
+        &self.coordinates[id * self.n_dims..(id + 1) * self.n_dims]
+    }
+
+    /// Returns the lower and upper bounds of the region of a node.
+    pub fn region(&self, id: NodeId) -> (&[f64], &[f64]) {
+        let range = id * self.n_dims..(id + 1) * self.n_dims;
+        (&self.lower[range.clone()], &self.upper[range])
+    }
+
+    /// Returns the coordinate of the point of a node in its split dimension.
+    pub fn split_value(&self, id: NodeId) -> f64 {
+        self.point(id)[self.nodes[id].split]
+    }
+
+    /// Returns whether `p` lies on the left side of the split of a node.
+    pub fn is_left_of(&self, id: NodeId, p: &[f64]) -> bool {
+        p[self.nodes[id].split] < self.split_value(id)
+    }
+
+    /// Returns the child of a node on the side of `p`.
+    pub fn child_towards(&self, id: NodeId, p: &[f64]) -> Option<NodeId> {
+        if self.is_left_of(id, p) {
+            self.nodes[id].left
+        } else {
+            self.nodes[id].right
+        }
+    }
+
+    /// Returns the child of a node on the other side than `p`.
+    pub fn child_away(&self, id: NodeId, p: &[f64]) -> Option<NodeId> {
+        if self.is_left_of(id, p) {
+            self.nodes[id].right
+        } else {
+            self.nodes[id].left
+        }
+    }
+
+    /// Returns the last node on the path from the root towards `p`, i.e. the parent `p` would be
+    /// inserted under.
+    pub fn leaf_for(&self, p: &[f64]) -> Option<NodeId> {
+        let mut id = self.root()?;
+        while let Some(child) = self.child_towards(id, p) {
+            id = child;
+        }
+        Some(id)
+    }
+
+    /// Inserts the point `p`, which must lie inside the input domain, and returns its node.
+    ///
+    /// `choose_split` returns the split dimension of the new node from the lower and upper bounds
+    /// of its region and the split dimension of its parent, `None` for the root.
+    pub fn insert(
+        &mut self,
+        p: &[f64],
+        choose_split: impl FnOnce(&[f64], &[f64], Option<usize>) -> usize,
+    ) -> NodeId {
+        let parent = self.leaf_for(p);
+        self.push(p, parent, choose_split)
+    }
+
+    /// Replaces the points of the tree with `points`, bulk-loaded into a balanced tree. The points
+    /// must lie inside the input domain.
+    ///
+    /// Every region is split at the median point along the dimension in which its points are
+    /// spread the widest, so the depth of the tree is about `log2(points.len())` whatever the
+    /// order of `points`.
+    pub fn build(&mut self, points: &[Vec<f64>]) {
+        self.clear();
+        if self.n_dims == 0 {
+            return;
+        }
+        let mut order = (0..points.len()).collect::<Vec<_>>();
+        self.build_subtree(points, &mut order, None);
+    }
+
+    /// Rebuilds the tree from its own points, balancing it.
+    pub fn rebalance(&mut self) {
+        if self.n_dims == 0 {
+            return;
+        }
+        let points = self
+            .coordinates
+            .chunks_exact(self.n_dims)
+            .map(<[f64]>::to_vec)
+            .collect::<Vec<_>>();
+        self.build(&points);
+    }
+
+    /// Builds the subtree of the points `order` refers to below `parent`.
+    fn build_subtree(&mut self, points: &[Vec<f64>], order: &mut [usize], parent: Option<NodeId>) {
+        if order.is_empty() {
+            return;
+        }
+
+        let spread = |d: usize| {
+            let (min, max) = order.iter().fold((f64::MAX, f64::MIN), |(min, max), &i| {
+                (min.min(points[i][d]), max.max(points[i][d]))
+            });
+            max - min
+        };
+        let split = (0..self.n_dims)
+            .max_by(|&a, &b| spread(a).total_cmp(&spread(b)))
+            .unwrap_or(0);
+
+        order.sort_unstable_by(|&a, &b| points[a][split].total_cmp(&points[b][split]));
+        // Points at the split value belong to the right side.
+        let mut median = order.len() / 2;
+        while median > 0 && points[order[median - 1]][split] == points[order[median]][split] {
+            median -= 1;
+        }
+
+        let id = self.push(&points[order[median]], parent, |_, _, _| split);
+        let (left, right) = order.split_at_mut(median);
+        self.build_subtree(points, left, Some(id));
+        self.build_subtree(points, &mut right[1..], Some(id));
+    }
+
+    /// Adds a node for the point `p` as a child of `parent`, on the side of `p`.
+    fn push(
+        &mut self,
+        p: &[f64],
+        parent: Option<NodeId>,
+        choose_split: impl FnOnce(&[f64], &[f64], Option<usize>) -> usize,
+    ) -> NodeId {
+        let id = self.nodes.len();
+        self.coordinates.extend_from_slice(p);
+
+        let (depth, parent_split) = match parent {
+            Some(parent) => {
+                let parent_region = parent * self.n_dims..(parent + 1) * self.n_dims;
+                self.lower.extend_from_within(parent_region.clone());
+                self.upper.extend_from_within(parent_region);
+                let split = self.nodes[parent].split;
+                let bound = id * self.n_dims + split;
+                if self.is_left_of(parent, p) {
+                    self.upper[bound] = self.split_value(parent);
+                    self.nodes[parent].left = Some(id);
+                } else {
+                    self.lower[bound] = self.split_value(parent);
+                    self.nodes[parent].right = Some(id);
+                }
+                (self.nodes[parent].depth + 1, Some(split))
+            }
+            None => {
+                self.lower.extend_from_slice(&self.domain_lower);
+                self.upper.extend_from_slice(&self.domain_upper);
+                (1, None)
+            }
+        };
+
+        let (lower, upper) = self.region(id);
+        let split = choose_split(lower, upper, parent_split);
+        self.nodes.push(KdNode {
+            split,
+            left: None,
+            right: None,
+            parent,
+            depth,
+        });
+        id
+    }
+
+    /// Returns the point nearest to `p`, or `None` if the tree is empty.
+    pub fn nearest(&self, p: &[f64]) -> Option<Neighbour<'_>> {
+        self.approx_nearest(p, 0.0)
+    }
+
+    /// Returns a point whose distance to `p` is at most `1 + epsilon` times the distance of the
+    /// nearest point, or `None` if the tree is empty.
+    ///
+    /// Subtrees whose regions are not closer to `p` than the nearest point so far divided by
+    /// `1 + epsilon` are skipped, so larger values of `epsilon` visit fewer nodes.
+    pub fn approx_nearest(&self, p: &[f64], epsilon: f64) -> Option<Neighbour<'_>> {
+        let (mut nearest, mut min) = (None, f64::INFINITY);
+        self.search(p, min, |id, distance| {
+            if distance < min {
+                nearest = Some(id);
+                min = distance;
+            }
+            min / (1.0 + epsilon)
+        });
+        nearest.map(|id| self.neighbour(id, min))
+    }
+
+    /// Returns the `k` points nearest to `p`, nearest first. Returns all points if the tree holds
+    /// fewer than `k`.
+    pub fn k_nearest(&self, p: &[f64], k: usize) -> Vec<Neighbour<'_>> {
+        if k == 0 {
+            return Vec::new();
+        }
+        let mut heap = BinaryHeap::with_capacity(k + 1);
+        self.search(p, f64::INFINITY, |id, distance| {
+            heap.push(HeapEntry { distance, id });
+            if heap.len() > k {
+                heap.pop();
+            }
+            match heap.peek() {
+                Some(furthest) if heap.len() == k => furthest.distance,
+                _ => f64::INFINITY,
+            }
+        });
+        heap.into_sorted_vec()
+            .into_iter()
+            .map(|e| self.neighbour(e.id, e.distance))
+            .collect()
+    }
+
+    /// Returns the points at a distance of at most `radius` from `p`, in no particular order.
+    pub fn within_radius(&self, p: &[f64], radius: f64) -> Vec<Neighbour<'_>> {
+        let mut neighbours = Vec::new();
+        self.search(p, radius, |id, distance| {
+            if distance <= radius {
+                neighbours.push(self.neighbour(id, distance));
+            }
+            radius
+        });
+        neighbours
+    }
+
+    /// Returns the number of points at a distance of at most `radius` from `p`.
+    pub fn count_within_radius(&self, p: &[f64], radius: f64) -> usize {
+        let mut count = 0;
+        self.search(p, radius, |_, distance| {
+            if distance <= radius {
+                count += 1;
+            }
+            radius
+        });
+        count
+    }
+
+    fn neighbour(&self, id: NodeId, distance: f64) -> Neighbour<'_> {
+        Neighbour {
+            id,
+            point: self.point(id),
+            distance,
+        }
+    }
+
+    /// Returns the distance from `p` to the region of a node, 0 if `p` lies inside it. No point in
+    /// the subtree of the node is closer to `p`.
+    pub fn region_distance(&self, id: NodeId, p: &[f64]) -> f64 {
+        let (lower, upper) = self.region(id);
+        p.iter()
+            .zip(lower.iter().zip(upper))
+            .map(|(&c, (&l, &u))| {
+                let outside = (l - c).max(c - u).max(0.0);
+                outside * outside
+            })
+            .sum::<f64>()
+            .sqrt()
+    }
+
+    /// Searches the tree by branch and bound, passing every node whose region is within `radius`
+    /// of `p` to `visit` together with its distance to `p`. `visit` returns the radius for the
+    /// rest of the search.
+    ///
+    /// The search goes depth first into the child on the side of `p` before the other child, and
+    /// skips subtrees whose regions are out of the radius. The child on the side of `p` has the
+    /// same region distance as its parent. The region of the other child differs from that of its
+    /// parent only in the split dimension, so its distance is updated from the bounds of the parent
+    /// in that dimension alone.
+    fn search(&self, p: &[f64], mut radius: f64, mut visit: impl FnMut(NodeId, f64) -> f64) {
+        let mut stack = Vec::from_iter(self.root().map(|id| {
+            let distance = self.region_distance(id, p);
+            (id, distance * distance)
+        }));
+        while let Some((id, region_distance_sq)) = stack.pop() {
+            if region_distance_sq > radius * radius {
+                continue;
+            }
+            radius = visit(id, distance(p, self.point(id)));
+
+            if let Some(far) = self.child_away(id, p) {
+                let split = self.nodes[id].split;
+                let line_distance = p[split] - self.split_value(id);
+                if line_distance * line_distance <= radius * radius {
+                    let (lower, upper) = self.region(id);
+                    let outside = (lower[split] - p[split])
+                        .max(p[split] - upper[split])
+                        .max(0.0);
+                    let far_distance_sq =
+                        region_distance_sq - outside * outside + line_distance * line_distance;
+                    stack.push((far, far_distance_sq));
+                }
+            }
+            if let Some(near) = self.child_towards(id, p) {
+                stack.push((near, region_distance_sq));
+            }
+        }
+    }
+}
+
+/// Entry of the max-heap of `KdTree::k_nearest`, ordered by distance
+#[derive(Debug, Clone, Copy)]
+struct HeapEntry {
+    distance: f64,
+    id: NodeId,
+}
+
+impl PartialEq for HeapEntry {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for HeapEntry {}
+
+impl PartialOrd for HeapEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for HeapEntry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.distance
+            .total_cmp(&other.distance)
+            .then(self.id.cmp(&other.id))
+    }
+}
+
+/// Returns the Euclidean distance between two points.
+pub fn distance(p1: &[f64], p2: &[f64]) -> f64 {
+    p1.iter()
+        .zip(p2)
+        .map(|(a, b)| (a - b) * (a - b))
+        .sum::<f64>()
+        .sqrt()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    #[test]
+    fn test_tree_links_and_regions() {
+        let mut rng = StdRng::seed_from_u64(31);
+        let input_domain = vec![vec![-100, 100], vec![0, 50], vec![-30, 70]];
+        let mut tree = KdTree::new(&input_domain);
+
+        for _ in 0..2 {
+            tree.clear();
+            assert_eq!(tree.leaf_for(&[0.0, 0.0, 0.0]), None);
+            for _ in 0..200 {
+                let p = input_domain
+                    .iter()
+                    .map(|b| rng.random_range(b[0] as f64..b[1] as f64))
+                    .collect::<Vec<_>>();
+                let id = tree.insert(&p, |_, _, parent_split| {
+                    parent_split.map_or(0, |split| (split + 1) % 3)
+                });
+                assert_eq!(tree.point(id), &p[..]);
+            }
+            assert_eq!(tree.len(), 200);
+
+            for id in 0..tree.len() {
+                let node = tree.node(id);
+                let (lower, upper) = tree.region(id);
+                let p = tree.point(id);
+                assert!((0..3).all(|d| lower[d] <= p[d] && p[d] <= upper[d]));
+
+                match node.parent {
+                    Some(parent) => {
+                        let parent_node = tree.node(parent);
+                        assert!(parent_node.left == Some(id) || parent_node.right == Some(id));
+                        assert_eq!(node.depth, parent_node.depth + 1);
+                        assert_eq!(node.split, (parent_node.split + 1) % 3);
+                    }
+                    None => assert_eq!((id, node.depth), (0, 1)),
+                }
+                for child in [node.left, node.right].into_iter().flatten() {
+                    assert_eq!(tree.node(child).parent, Some(id));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_queries_match_brute_force() {
+        let mut rng = StdRng::seed_from_u64(41);
+        let input_domain = vec![vec![0, 100]; 4];
+        let random_point = |rng: &mut StdRng| {
+            input_domain
+                .iter()
+                .map(|b| rng.random_range(b[0] as f64..b[1] as f64))
+                .collect::<Vec<_>>()
+        };
+
+        let mut tree = KdTree::new(&input_domain);
+        assert_eq!(tree.nearest(&[0.0; 4]), None);
+        let points = (0..500).map(|_| random_point(&mut rng)).collect::<Vec<_>>();
+        for p in &points {
+            tree.insert(p, |_, _, parent_split| {
+                parent_split.map_or(0, |split| (split + 1) % 4)
+            });
+        }
+
+        for _ in 0..100 {
+            let q = random_point(&mut rng);
+            let mut distances = points.iter().map(|p| distance(&q, p)).collect::<Vec<_>>();
+            distances.sort_by(f64::total_cmp);
+
+            let nearest = tree.nearest(&q).unwrap();
+            assert_eq!(nearest.distance, distances[0]);
+            assert_eq!(nearest.point, &points[nearest.id][..]);
+
+            let k_nearest = tree.k_nearest(&q, 7);
+            assert_eq!(
+                k_nearest.iter().map(|n| n.distance).collect::<Vec<_>>(),
+                distances[..7]
+            );
+            assert_eq!(tree.k_nearest(&q, 0), vec![]);
+            assert_eq!(tree.k_nearest(&q, 1000).len(), 500);
+
+            let radius = distances[20];
+            let mut within = tree.within_radius(&q, radius);
+            within.sort_by(|a, b| a.distance.total_cmp(&b.distance));
+            assert_eq!(
+                within.iter().map(|n| n.distance).collect::<Vec<_>>(),
+                distances[..=20]
+            );
+            assert_eq!(tree.count_within_radius(&q, radius), 21);
+            assert_eq!(tree.count_within_radius(&q, 0.0), 0);
+        }
+    }
+
+    #[test]
+    fn test_build_balanced() {
+        let mut rng = StdRng::seed_from_u64(43);
+        let input_domain = vec![vec![0, 1000]; 3];
+        // Sorted points make a list out of a tree built by insertion, and duplicates test the
+        // median split at equal values.
+        let mut points = (0..1000)
+            .map(|_| {
+                input_domain
+                    .iter()
+                    .map(|b| rng.random_range(b[0]..b[1]) as f64)
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        points.extend_from_within(..100);
+        points.sort_by(|a, b| a[0].total_cmp(&b[0]));
+
+        let mut tree = KdTree::new(&input_domain);
+        for p in &points {
+            tree.insert(p, |_, _, _| 0);
+        }
+        let max_depth = |tree: &KdTree| (0..tree.len()).map(|id| tree.node(id).depth).max();
+        assert!(max_depth(&tree) > Some(100));
+
+        tree.rebalance();
+        assert_eq!(tree.len(), 1100);
+        assert!(max_depth(&tree) <= Some(14));
+
+        for id in 0..tree.len() {
+            let node = tree.node(id);
+            let (lower, upper) = tree.region(id);
+            let p = tree.point(id);
+            assert!((0..3).all(|d| lower[d] <= p[d] && p[d] <= upper[d]));
+            if let Some(parent) = node.parent {
+                assert_eq!(tree.child_towards(parent, p), Some(id));
+            }
+        }
+
+        for _ in 0..50 {
+            let q = [(); 3].map(|_| rng.random_range(0.0..1000.0));
+            let brute_force = points
+                .iter()
+                .map(|p| distance(&q, p))
+                .fold(f64::MAX, f64::min);
+            assert_eq!(tree.nearest(&q).unwrap().distance, brute_force);
+        }
+    }
+
+    #[test]
+    fn test_approx_nearest_error_bound() {
+        let mut rng = StdRng::seed_from_u64(53);
+        let input_domain = vec![vec![-5000, 5000]; 8];
+        let random_point = |rng: &mut StdRng| {
+            input_domain
+                .iter()
+                .map(|b| rng.random_range(b[0] as f64..b[1] as f64))
+                .collect::<Vec<_>>()
+        };
+
+        let mut tree = KdTree::new(&input_domain);
+        for _ in 0..2000 {
+            tree.insert(&random_point(&mut rng), |_, _, parent_split| {
+                parent_split.map_or(0, |split| (split + 1) % 8)
+            });
+        }
+
+        for epsilon in [0.0, 0.1, 0.5, 2.0] {
+            for _ in 0..100 {
+                let q = random_point(&mut rng);
+                let exact = tree.nearest(&q).unwrap();
+                let approx = tree.approx_nearest(&q, epsilon).unwrap();
+                assert_eq!(approx.distance, distance(&q, approx.point));
+                assert!(approx.distance >= exact.distance);
+                assert!(approx.distance <= (1.0 + epsilon) * exact.distance);
+                if epsilon == 0.0 {
+                    assert_eq!(approx.distance, exact.distance);
+                }
+            }
+        }
+    }
+}
@@ -15,10 +15,9 @@ impl Point {
         }
     }
 
-    pub fn generate_rand_p(bound: &[Vec<i32>]) -> Self {
+    pub fn generate_rand_p<R: Rng + ?Sized>(bound: &[Vec<i32>], rng: &mut R) -> Self {
         let n = bound.len();
         let mut p = Point::new(n);
-        let mut rng = rand::rng();
         (0..n).for_each(|i| {
             p.coordinates[i] = (bound[i][0] as f32) + ((bound[i][1] - bound[i][0]) as f64 * rng.random::<f64>()) as f32;
             // p.coordinates[i] = rng.random_range(bound[i][0] as f32..bound[i][1] as f32);
@@ -26,6 +25,18 @@ impl Point {
         p
     }
 
+    /// Generates a random point inside a sub-region of an input domain, given as the
+    /// `[lower, upper]` bounds of each dimension.
+    pub fn generate_rand_p_in<R: Rng + ?Sized>(boundary: &[Vec<f64>], rng: &mut R) -> Self {
+        let n = boundary.len();
+        let mut p = Point::new(n);
+        (0..n).for_each(|i| {
+            p.coordinates[i] =
+                (boundary[i][0] + (boundary[i][1] - boundary[i][0]) * rng.random::<f64>()) as f32;
+        });
+        p
+    }
+
     pub fn get_distance(p1: &Self, p2: &Self) -> f32 {
         let mut sum_sq = 0.0;
         for i in 0..p1.n {
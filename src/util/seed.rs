@@ -0,0 +1,12 @@
+/// Derives the seed of an independent random number stream from a master `seed` and the index of
+/// the stream, e.g. the index of a trial in an experiment.
+///
+/// Using derived seeds instead of drawing them from a shared random number generator lets any
+/// single stream be replayed from the master seed alone.
+pub fn derive_seed(seed: u64, stream: u64) -> u64 {
+    // SplitMix64 finaliser
+    let mut z = seed ^ stream.wrapping_add(1).wrapping_mul(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
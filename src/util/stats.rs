@@ -0,0 +1,195 @@
+/// Two-sided 97.5% quantile of the standard normal distribution, for 95% confidence intervals
+pub const Z_95: f64 = 1.959_963_984_540_054;
+
+/// Returns the Wilson score confidence interval `(lower, upper)` of a proportion with `successes`
+/// out of `n` trials, for the standard normal quantile `z`.
+///
+/// Unlike the normal approximation the interval stays inside `[0, 1]` and does not collapse to a
+/// point when there are no successes or no failures.
+pub fn wilson_interval(successes: usize, n: usize, z: f64) -> (f64, f64) {
+    if n == 0 {
+        return (0.0, 1.0);
+    }
+    let n = n as f64;
+    let p = successes as f64 / n;
+    let z2 = z * z;
+    let centre = (p + z2 / (2.0 * n)) / (1.0 + z2 / n);
+    let half_width = z / (1.0 + z2 / n) * (p * (1.0 - p) / n + z2 / (4.0 * n * n)).sqrt();
+    (
+        (centre - half_width).max(0.0),
+        (centre + half_width).min(1.0),
+    )
+}
+
+/// Mean, sample standard deviation and 95% confidence interval of the mean of a sample
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Summary {
+    pub mean: f64,
+    pub sd: f64,
+    pub lower: f64,
+    pub upper: f64,
+}
+
+impl Summary {
+    /// Summarises a sample, with the normal approximation of the confidence interval.
+    pub fn of(sample: &[f64]) -> Self {
+        let n = sample.len() as f64;
+        let mean = sample.iter().sum::<f64>() / n;
+        let sd = if sample.len() > 1 {
+            (sample.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (n - 1.0)).sqrt()
+        } else {
+            0.0
+        };
+        let half_width = Z_95 * sd / n.sqrt();
+        Self {
+            mean,
+            sd,
+            lower: mean - half_width,
+            upper: mean + half_width,
+        }
+    }
+
+    /// Summarises a proportion of `successes` out of `n` trials, with the Wilson score interval.
+    pub fn of_proportion(successes: usize, n: usize) -> Self {
+        let p = successes as f64 / n as f64;
+        let sd = if n > 1 {
+            (p * (1.0 - p) * n as f64 / (n - 1) as f64).sqrt()
+        } else {
+            0.0
+        };
+        let (lower, upper) = wilson_interval(successes, n, Z_95);
+        Self {
+            mean: p,
+            sd,
+            lower,
+            upper,
+        }
+    }
+}
+
+/// Returns the Mann-Whitney U statistic of sample `a` against sample `b` and its two-sided
+/// p-value.
+///
+/// The p-value uses the normal approximation with tie and continuity correction, which is accurate
+/// for the sample sizes of the experiments, i.e. from about 20 values per sample.
+pub fn mann_whitney_u(a: &[f64], b: &[f64]) -> (f64, f64) {
+    let (m, n) = (a.len() as f64, b.len() as f64);
+    let mut values = a
+        .iter()
+        .map(|&x| (x, true))
+        .chain(b.iter().map(|&x| (x, false)))
+        .collect::<Vec<_>>();
+    values.sort_by(|x, y| x.0.total_cmp(&y.0));
+
+    // Sum of the ranks of `a`, with tied values sharing their mean rank
+    let mut rank_sum = 0.0;
+    let mut tie_sum = 0.0;
+    let mut start = 0;
+    while start < values.len() {
+        let end = start + values[start..].partition_point(|v| v.0 == values[start].0);
+        let n_tied = (end - start) as f64;
+        let rank = (start + end + 1) as f64 / 2.0;
+        rank_sum += rank * values[start..end].iter().filter(|v| v.1).count() as f64;
+        tie_sum += n_tied.powi(3) - n_tied;
+        start = end;
+    }
+
+    let u = rank_sum - m * (m + 1.0) / 2.0;
+    let n_total = m + n;
+    let variance = m * n / 12.0 * ((n_total + 1.0) - tie_sum / (n_total * (n_total - 1.0)));
+    if variance <= 0.0 {
+        return (u, 1.0);
+    }
+    let z = ((u - m * n / 2.0).abs() - 0.5).max(0.0) / variance.sqrt();
+    (u, (2.0 * (1.0 - normal_cdf(z))).min(1.0))
+}
+
+/// Returns the Vargha-Delaney Â12 effect size, the probability that a value of sample `a` is
+/// larger than one of sample `b`, counting ties as half.
+///
+/// 0.5 means no effect. 0.56, 0.64 and 0.71 are the usual thresholds of a small, medium and large
+/// effect, and likewise below 0.5.
+pub fn vargha_delaney_a12(a: &[f64], b: &[f64]) -> f64 {
+    let (u, _) = mann_whitney_u(a, b);
+    u / (a.len() * b.len()) as f64
+}
+
+/// Returns the p-values adjusted for multiple comparisons with the Holm-Bonferroni method, in the
+/// order of `p_values`.
+pub fn holm_correction(p_values: &[f64]) -> Vec<f64> {
+    let m = p_values.len();
+    let mut order = (0..m).collect::<Vec<_>>();
+    order.sort_by(|&i, &j| p_values[i].total_cmp(&p_values[j]));
+
+    let mut adjusted = vec![0.0; m];
+    let mut running_max = 0.0_f64;
+    for (k, &i) in order.iter().enumerate() {
+        running_max = running_max.max(((m - k) as f64 * p_values[i]).min(1.0));
+        adjusted[i] = running_max;
+    }
+    adjusted
+}
+
+/// Returns the cumulative distribution function of the standard normal distribution at `z`.
+pub fn normal_cdf(z: f64) -> f64 {
+    0.5 * erfc(-z / std::f64::consts::SQRT_2)
+}
+
+/// Complementary error function, with a relative error below 1.2e-7 (Numerical Recipes, erfcc).
+fn erfc(x: f64) -> f64 {
+    let t = 1.0 / (1.0 + 0.5 * x.abs());
+    const COEFFICIENTS: [f64; 10] = [
+        -1.265_512_23,
+        1.000_023_68,
+        0.374_091_96,
+        0.096_784_18,
+        -0.186_288_06,
+        0.278_868_07,
+        -1.135_203_98,
+        1.488_515_87,
+        -0.822_152_23,
+        0.170_872_77,
+    ];
+    let polynomial = COEFFICIENTS.iter().rev().fold(0.0, |acc, c| acc * t + c);
+    let y = t * (-x * x + polynomial).exp();
+    if x >= 0.0 {
+        y
+    } else {
+        2.0 - y
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_mann_whitney_u() {
+        let a = [1.0, 2.0, 3.0, 4.0, 5.0];
+        let b = [6.0, 7.0, 8.0, 9.0, 10.0];
+        let (u, p) = mann_whitney_u(&a, &b);
+        assert_eq!(u, 0.0);
+        assert!((p - 0.012_185).abs() < 1e-5);
+        assert_eq!(vargha_delaney_a12(&a, &b), 0.0);
+        assert_eq!(vargha_delaney_a12(&b, &a), 1.0);
+
+        // Identical samples with ties
+        let c = [3.0, 3.0, 1.0, 2.0];
+        let (u, p) = mann_whitney_u(&c, &c);
+        assert_eq!(u, 8.0);
+        assert!(p > 0.999_999);
+        assert_eq!(vargha_delaney_a12(&c, &c), 0.5);
+
+        assert!((normal_cdf(1.959_963_984_540_054) - 0.975).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_holm_correction() {
+        let adjusted = holm_correction(&[0.01, 0.04, 0.03, 0.5]);
+        let expected = [0.04, 0.09, 0.09, 0.5];
+        assert!(adjusted
+            .iter()
+            .zip(expected)
+            .all(|(p, e)| (p - e).abs() < 1e-12));
+    }
+}